@@ -1,17 +1,114 @@
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
 use iced::{
-    Task,
-    widget::pane_grid::{self},
+    Task, keyboard,
+    widget::{
+        image, operation,
+        pane_grid::{self},
+        scrollable,
+    },
 };
+use regex::{Regex, RegexBuilder};
 use syntect::{highlighting::ThemeSet, parsing::SyntaxSet};
 
-use crate::fs_utils::{FileNode, determine_file_type, read_dir};
+use crate::archive::{
+    ExtractEvent, archive_parent, archive_root, extract_with_progress, extraction_folder_name,
+    is_archive, is_extractable,
+};
+use crate::associations::{FileAssociations, OpenBehavior, ViewPreferences, WrapMode};
+use crate::commands::filter_commands;
+use crate::compare::{Comparison, compare_dirs};
+use crate::dialogs::{pick_folder, save_file};
+#[cfg(feature = "serde")]
+use crate::export::{ExportTarget, listing_to_json, walk_listing};
+use crate::fs_ops::{
+    CopyEvent, PlannedRename, UndoableOp, apply_batch_rename, copy_file_with_progress,
+    create_folder, delete_path, move_path, plan_batch_rename, rename_path, save_copy,
+    unique_destination,
+};
+use crate::fs_utils::{
+    CancelToken, Cancelable, DATA_URI_MAX_SIZE, DEFAULT_MAX_SEARCH_RESULTS, DEFAULT_MAX_WALK_DEPTH,
+    DiskSpace, ExtensionDisplay, FileDetails, FileNode, FileSource, PREVIEW_MAX_LINES,
+    SearchResults, SortMode, TextEncoding, complete_path, count_children, data_uri, decode_text,
+    determine_file_type, determine_language, directory_size, disk_space, expand_home,
+    file_contains, file_details, has_more_entries_than, hex_dump, human_size, is_binary,
+    is_symlink_cycle, list_dir, longest_common_prefix, mime_type, open_with, read_bytes,
+    read_bytes_at, read_dir, read_dir_cancelable, resolve_typed_path, search_tree,
+    shebang_language, sort_nodes, text_preview,
+};
+use crate::hashing::{HashAlgorithm, HashEvent, hash_file_with_progress};
+use crate::highlight::{HighlightedFile, SyntaxTheme, find_syntax, highlight_file};
+use crate::logging;
+use crate::places::{Place, quick_access_places};
+use crate::recent::RecentFiles;
+#[cfg(feature = "serde")]
+use crate::settings::Settings;
+use crate::thumbnails::{ThumbnailCache, ThumbnailKey, generate_thumbnail};
 use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
     fs::{self, canonicalize},
-    path::Path,
+    ops::Range,
+    path::{Path, PathBuf},
     process::exit,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+/// The default number of file nodes shown per page of the file tree
+const DEFAULT_PAGE_SIZE: usize = 200;
+
+/// The minimum score for a file name to match a fuzzy search
+const MIN_FUZZY_SCORE: i64 = 10;
+
+/// How long typing has to pause before the file name search runs
+const SEARCH_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long typing has to pause before the type-ahead starts over
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// The default number of entries above which opening a directory asks for confirmation
+const DEFAULT_LARGE_DIR_THRESHOLD: usize = 10_000;
+
+/// The numbers of entries above which opening a directory asks for confirmation
+/// users can pick from
+pub const LARGE_DIR_THRESHOLD_OPTIONS: [usize; 4] = [1_000, 10_000, 100_000, 1_000_000];
+
+/// The caps on the number of results of a recursive search users can pick from
+pub const MAX_SEARCH_RESULTS_OPTIONS: [usize; 5] = [100, 500, 1000, 5000, 10000];
+
+/// The id of the text input of the command palette
+pub(crate) const COMMAND_PALETTE_INPUT_ID: &str = "command-palette-input";
+
+/// The id of the text input finding text in the opened file
+pub(crate) const FIND_INPUT_ID: &str = "find-input";
+
+/// The id of the text input naming a new folder
+pub(crate) const NEW_FOLDER_INPUT_ID: &str = "new-folder-input";
+
+/// The id of the text input renaming a file in the file tree
+pub(crate) const RENAME_INPUT_ID: &str = "rename-input";
+
+/// The id of the scrollable showing the contents of the opened file
+pub(crate) const FILE_CONTENTS_SCROLLABLE_ID: &str = "file-contents-scrollable";
+
+/// The id of the scrollable showing the file tree
+pub(crate) const FILE_TREE_SCROLLABLE_ID: &str = "file-tree-scrollable";
+
+/// The default number of bytes shown at once in the hex viewer
+const DEFAULT_HEX_VIEW_WINDOW: u64 = 64 * 1024;
+
+/// The numbers of bytes shown at once in the hex viewer users can pick from
+pub const HEX_VIEW_WINDOW_OPTIONS: [u64; 4] = [16 * 1024, 64 * 1024, 256 * 1024, 1024 * 1024];
+
+/// The number of bytes of a text file read at once. Larger files are truncated
+/// until more is loaded.
+const TEXT_VIEW_CHUNK_BYTES: u64 = 5 * 1024 * 1024;
+
+/// The smallest font size the viewer can be set to
+pub const MIN_VIEW_FONT_SIZE: f32 = 8.0;
+
+/// The largest font size the viewer can be set to
+pub const MAX_VIEW_FONT_SIZE: f32 = 48.0;
+
 /// The application state
 #[derive(Debug)]
 pub struct FileExplorerApp {
@@ -21,22 +118,218 @@ pub struct FileExplorerApp {
     pub opened_file: Option<FileNode>,
     /// The contents of the `opened_file`
     pub opened_file_contents: Result<String, std::io::Error>,
-    /// The type of the `opened_file` (if present)
+    /// The language the `opened_file` is highlighted as (if it is a text file)
     pub opened_file_type: Option<String>,
+    /// How the `opened_file` is displayed
+    pub opened_file_behavior: OpenBehavior,
+    /// The view preferences the `opened_file` is presented with
+    pub opened_file_view: ViewPreferences,
+    /// The text searched for in the `opened_file`
+    pub find_query: String,
+    /// The indices of the lines of the `opened_file` matching the `find_query`
+    pub find_matches: Vec<usize>,
+    /// The index in `find_matches` of the match jumped to last (if any)
+    pub find_current: Option<usize>,
+    /// The metadata of the `opened_file` (`None` for files within archives)
+    pub opened_file_details: Option<FileDetails>,
+    /// Whether the details of the `opened_file` are shown below its name
+    pub show_file_details: bool,
+    /// Statistics about the text of the `opened_file` (if it is a text file)
+    pub opened_file_stats: Option<FileStats>,
+    /// The decoded `opened_file` (if it is shown in the image viewer)
+    pub opened_image: Option<OpenedImage>,
+    /// The encoding the `opened_file` was decoded with (if it is a text file)
+    pub opened_file_encoding: Option<TextEncoding>,
+    /// The maximum number of bytes of the `opened_file` read as text
+    pub opened_file_read_limit: u64,
+    /// A flag set when the text file is longer than the `opened_file_read_limit`,
+    /// so only its start is shown
+    pub opened_file_truncated: bool,
+    /// A flag set when the file opened in the text viewer looks like binary
+    /// data, so it is described instead of shown
+    pub opened_file_binary: bool,
     /// The children of the `opened_dir`
     pub files: Vec<FileNode>,
+    /// The space of the volume containing the `opened_dir` (if it could be read)
+    pub disk_space: Option<DiskSpace>,
+    /// The index in `files` of the selected file node (if any)
+    pub selected_index: Option<usize>,
+    /// The absolute paths of the file nodes selected with Ctrl and Shift clicks,
+    /// which batch actions (such as deleting) apply to
+    pub selection: HashSet<String>,
+    /// The start of a file name typed while the file tree has the focus
+    pub type_ahead: String,
+    /// When the last key of `type_ahead` was typed
+    pub type_ahead_at: Option<Instant>,
+    /// The page of the (filtered) `files` currently shown, starting at 0
+    pub page: usize,
+    /// The number of `files` shown per page
+    pub page_size: usize,
     /// The search filter for the file tree
     pub filters: Filters,
-    /// Whether the application is in dark mode
+    /// The maximum number of results of a recursive search
+    pub max_search_results: usize,
+    /// A flag set when `files` holds the results of a recursive search instead of
+    /// the children of the `opened_dir`
+    pub showing_search_results: bool,
+    /// A flag set when the recursive search stopped at `max_search_results`
+    pub search_results_truncated: bool,
+    /// Whether the system is in dark mode
     pub system_color_mode: dark_light::Mode,
+    /// Whether the application follows the system or is always light or dark
+    pub color_mode: ColorMode,
+    /// Whether long lines wrap in the viewer, unless remembered for the file type
+    pub wrap_mode: WrapMode,
     /// The state of the pane grid
     pub panes: pane_grid::State<PaneContent>,
+    /// Whether the file tree panel is shown
+    pub sidebar_visible: bool,
+    /// Whether the log panel is shown
+    pub log_panel_open: bool,
+    /// The absolute paths of the directories visited, oldest first
+    pub history: Vec<String>,
+    /// The position of the `opened_dir` within the `history`
+    pub history_index: usize,
+    /// The directory tabs. The entry of the `active_tab` is out of date, as the
+    /// state of the active tab lives in the fields of the application.
+    pub tabs: Vec<Tab>,
+    /// The index of the tab currently shown
+    pub active_tab: usize,
+    /// Whether clicking a folder opens it in a new tab (it does with Ctrl either way)
+    pub open_dirs_in_new_tab: bool,
+    /// The keyboard modifiers currently held
+    pub modifiers: keyboard::Modifiers,
+    /// Whether the quick access section is shown above the file tree
+    pub quick_access_open: bool,
+    /// The locations listed in the quick access section
+    pub places: Vec<Place>,
+    /// The files opened most recently, listed in the quick access section
+    pub recent_files: RecentFiles,
+    /// The absolute paths of the bookmarked directories, listed in the quick access section
+    pub bookmarks: Vec<String>,
+    /// The previews of the files hovered in the file tree by path (`None` for
+    /// files that cannot be previewed, or are still being read)
+    pub previews: HashMap<String, Option<String>>,
+    /// The filters remembered for specific directories, by absolute path
+    pub dir_views: HashMap<String, DirViewPreferences>,
+    /// The filters of the directories without remembered ones
+    pub default_dir_view: DirViewPreferences,
+    /// Whether modification times are shown relative to now (such as "5m ago")
+    pub relative_times: bool,
+    /// Whether images in the file tree show a thumbnail beside their name
+    pub show_thumbnails: bool,
+    /// The thumbnails of the images shown in the file tree
+    pub thumbnails: ThumbnailCache,
+    /// Whether folders in the file tree show how many items they contain
+    pub show_child_counts: bool,
+    /// The number of items in the folders shown in the file tree by path (`None`
+    /// for folders that cannot be read, or are still being counted)
+    pub child_counts: HashMap<String, Option<usize>>,
+    /// Whether symlinked folders are opened at the path of their target
+    /// instead of the link's own path
+    pub resolve_symlinks: bool,
+    /// Whether recursive operations (searching, measuring) descend into symlinked folders
+    pub follow_symlinks: bool,
     /// Syntax highlighting data
     pub highlighting: Highlighting,
+    /// The syntax highlighting of the opened file, computed once per file and color mode
+    pub highlighted: Option<HighlightedFile>,
     // The file node for the file info modal (if open)
     pub file_info_modal_node: Option<FileNode>,
     /// A boolean to track if the file info modal is open
     pub file_info_modal_open: bool,
+    /// The total size of the directory in the file info modal and whether the
+    /// depth limit cut the walk short (`None` while it is being computed)
+    pub file_info_dir_size: Option<(u64, bool)>,
+    /// The token cancelling the measurement of the directory in the file info modal
+    pub file_info_dir_size_cancel: Option<CancelToken>,
+    /// The maximum depth of recursive directory walks
+    pub max_walk_depth: usize,
+    /// The state of the address bar used to jump to a path
+    pub address_bar: AddressBar,
+    /// The associations deciding how each file type is opened
+    pub associations: FileAssociations,
+    /// A boolean to track if the file associations modal is open
+    pub associations_modal_open: bool,
+    /// A boolean to track if the settings modal is open
+    pub settings_modal_open: bool,
+    /// How much space each row of the file tree takes
+    pub row_density: RowDensity,
+    /// How far the children of an expanded directory are indented
+    pub tree_indent: TreeIndent,
+    /// Whether tabs and trailing spaces are rendered visibly in the viewer
+    pub show_whitespace: bool,
+    /// Whether a gutter with the line numbers is shown beside the text in the viewer
+    pub show_line_numbers: bool,
+    /// The theme the viewer highlights text with
+    pub syntax_theme: SyntaxTheme,
+    /// How file extensions are shown in the file tree
+    pub extension_display: ExtensionDisplay,
+    /// The order the file tree is sorted in
+    pub sort_mode: SortMode,
+    /// The extension typed into the file associations modal
+    pub new_association_extension: String,
+    /// A message describing the result of the last operation, shown in the status bar
+    pub status_message: Option<String>,
+    /// The bytes copied and the total bytes of the copy in progress (if any)
+    pub copy_progress: Option<(u64, u64)>,
+    /// The entries extracted and the total entries of the extraction in progress (if any)
+    pub extract_progress: Option<(usize, usize)>,
+    /// The operations that can be undone, most recent last
+    pub undo_stack: Vec<UndoableOp>,
+    /// The files or folders copied or cut, waiting to be pasted into another folder
+    pub clipboard: Option<(Vec<PathBuf>, ClipboardOp)>,
+    /// A boolean to track if the command palette is open
+    pub command_palette_open: bool,
+    /// The text typed into the command palette
+    pub command_palette_query: String,
+    /// The state of the batch rename modal (if open)
+    pub batch_rename: Option<BatchRename>,
+    /// The name typed for a new folder (while it is being named)
+    pub new_folder_name: Option<String>,
+    /// The path of the file being renamed in the file tree and the name typed for it
+    pub renaming: Option<(String, String)>,
+    /// The number of entries above which opening a directory asks for confirmation
+    pub large_dir_threshold: usize,
+    /// The number of bytes shown at once in the hex viewer
+    pub hex_view_window: u64,
+    /// The offset of the first byte shown in the hex viewer
+    pub hex_view_offset: u64,
+    /// The directory waiting for confirmation to be read, as it has more
+    /// than `large_dir_threshold` entries
+    pub large_dir_prompt: Option<LargeDirPrompt>,
+    /// The listing of the opened directory being read in the background, if any
+    pub pending_dir_load: Option<PendingDirLoad>,
+    /// The kind and description of the error reading the `opened_dir`, if it
+    /// could not be listed
+    pub dir_error: Option<(std::io::ErrorKind, String)>,
+    /// How far the file tree is scrolled down, in pixels
+    pub file_tree_offset: f32,
+    /// The page and scroll offset of the file tree in the directories opened
+    /// before, keyed by their absolute path
+    pub dir_scroll_offsets: HashMap<String, (usize, f32)>,
+    /// The page and scroll offset to restore once the opened directory is listed
+    pub pending_scroll: Option<(usize, f32)>,
+    /// The files or folders waiting for confirmation to be deleted
+    pub pending_delete: Vec<FileNode>,
+    /// The algorithm used to hash files
+    pub hash_algorithm: HashAlgorithm,
+    /// The hash of the file shown in the file info modal (if computed)
+    pub file_hash: Option<FileHash>,
+    /// The abort handle of the hashing in progress
+    pub file_hash_handle: Option<iced::task::Handle>,
+    /// The comparison of the `opened_dir` against another directory (if shown)
+    pub comparison: Option<Comparison>,
+    /// Whether comparing directories compares the contents of files
+    pub compare_contents: bool,
+    /// The command files are opened externally with (the default application
+    /// of the operating system if empty)
+    pub external_editor: String,
+    /// The width and height of the window, once it was resized
+    pub window_size: Option<(f32, f32)>,
+    /// The settings as they were last saved
+    #[cfg(feature = "serde")]
+    pub saved_settings: Settings,
 }
 
 /// The actions that can occur for the application. During the `update` function,
@@ -51,22 +344,352 @@ pub enum Action {
     CloseFile,
     // An action for when the user attempts to navigate up a directory
     GoBack(),
+    // Goes back to the previously visited directory
+    HistoryBack,
+    // Goes forward to the directory visited before going back
+    HistoryForward,
     // Schedules a debounced search for a file by name. Calls SearchByFilename after delay
     DebouncedSearch(String),
     // Search for a file by name
     SearchByFilename(String),
+    // An action for when the content search text changed
+    SetContentSearch(String),
+    // Shows only the listed files whose contents contain the text
+    SearchByContent(String),
+    // An action for when the files containing the searched text were found
+    ContentSearchFinished(String, Vec<String>),
+    // Changes how the file name search is matched
+    SetSearchMode(SearchMode),
+    // An action for when the file tree was scrolled to the offset
+    FileTreeScrolled(f32),
+    // Matches the file name search with the same case only
+    SetCaseSensitive(bool),
+    // Matches the file name search against whole words only
+    SetWholeWord(bool),
+    // Shows only folders, only files or both
+    SetKindFilter(KindFilter),
+    // Shows or hides the files whose name starts with a dot
+    SetShowHidden(bool),
+    // Remembers the filters of the opened directory (or forgets them)
+    RememberDirView(bool),
+    // Uses the current filters for every directory, forgetting the remembered ones
+    ApplyDirViewToAll,
+    // Searches the subfolders of the opened directory too
+    SetRecursiveSearch(bool),
+    // Changes the maximum number of results of a recursive search
+    SetMaxSearchResults(usize),
+    // Opens symlinked folders at the path of their target (or the link's own path)
+    SetResolveSymlinks(bool),
+    // Descends into symlinked folders in recursive operations
+    SetFollowSymlinks(bool),
+    // An action for when a recursive search for the given name completed (or was cancelled)
+    RecursiveSearchFinished(String, Cancelable<SearchResults>),
     // An action for when the panes are resized
     PanesResized(pane_grid::ResizeEvent),
+    // An action for when the window is resized
+    WindowResized(iced::Size),
     // An action for when the context menu is opened on a file
     OpenContextMenu(ContextMenuAction),
     // An action for when the file info modal is closed
     CloseFileInfoModal,
+    // An action for when the size of a directory was computed for the file info modal
+    DirectorySizeComputed(String, u64, bool),
+    // An action for when the text of the address bar changes
+    AddressBarChanged(String),
+    // Completes the path typed in the address bar (bound to Tab)
+    CompleteAddress,
+    // Navigates to the given path, opening it as a directory or file
+    NavigateTo(String),
+    // An action for when the settings modal is opened
+    OpenSettingsModal,
+    // An action for when the settings modal is closed
+    CloseSettingsModal,
+    // Changes how much space each row of the file tree takes
+    SetRowDensity(RowDensity),
+    // Changes how far the children of an expanded directory are indented
+    SetTreeIndent(TreeIndent),
+    // Makes the application follow the system or always be light or dark
+    SetColorMode(ColorMode),
+    // Changes which files wrap their long lines when opened
+    SetWrapMode(WrapMode),
+    // Shows or hides tabs and trailing spaces in the viewer
+    SetShowWhitespace(bool),
+    // Shows or hides the line numbers in the viewer
+    SetShowLineNumbers(bool),
+    // Changes the theme the viewer highlights text with
+    SetSyntaxTheme(SyntaxTheme),
+    // Changes how file extensions are shown in the file tree
+    SetExtensionDisplay(ExtensionDisplay),
+    // Changes the order the file tree is sorted in
+    SetSortMode(SortMode),
+    // An action for when the file associations modal is opened
+    OpenAssociationsModal,
+    // An action for when the file associations modal is closed
+    CloseAssociationsModal,
+    // An action for when the extension typed in the associations modal changes
+    NewAssociationExtensionChanged(String),
+    // Associates an extension with an open behavior
+    SetAssociation(String, OpenBehavior),
+    // Removes the association for an extension
+    RemoveAssociation(String),
+    // Shows the next page of the file tree
+    NextPage,
+    // Shows the previous page of the file tree
+    PreviousPage,
+    // An action for when a destination folder was picked to copy files to
+    CopyToFolderPicked(Vec<String>, Option<PathBuf>),
+    // An action for the progress of a file copy
+    CopyProgress(CopyEvent),
+    // An action for when a folder was picked to extract an archive into
+    ExtractToFolderPicked(String, Option<PathBuf>),
+    // An action for the progress of an archive extraction
+    ExtractProgress(ExtractEvent),
+    // Opens a save dialog to save a copy of the opened file
+    SaveAs,
+    // An action for when the path to save the opened file to was picked
+    SaveAsPathPicked(Option<PathBuf>),
+    // An action for when saving a copy of the opened file completed
+    SaveAsFinished(Result<PathBuf, String>),
+    // Exports the shown files (or all descendants, if recursive) as JSON
+    #[cfg(feature = "serde")]
+    ExportListing(ExportTarget, bool),
+    // An action for when the path to export the listing to was picked
+    #[cfg(feature = "serde")]
+    ExportListingPathPicked(Option<PathBuf>, bool),
+    // An action for when the JSON of the listing to copy was built
+    #[cfg(feature = "serde")]
+    ListingJsonReady(Result<String, String>),
+    // An action for when the listing was written to a file
+    #[cfg(feature = "serde")]
+    ListingExported(Result<PathBuf, String>),
+    // Decodes the opened file again with the given encoding
+    SetEncoding(TextEncoding),
+    // Reads the next chunk of a truncated text file
+    LoadMoreText,
+    // Shows the opened file with another viewer (text or hex)
+    SetRenderMode(OpenBehavior),
+    // Wraps or scrolls the long lines of the opened file
+    SetWrap(bool),
+    // Changes the font size of the opened file
+    SetViewFontSize(f32),
+    // Remembers (or forgets) the view of the opened file for all files of its type
+    RememberViewForType(bool),
+    // An action for when the text searched for in the opened file changes
+    FindQueryChanged(String),
+    // Scrolls the opened file to the given line
+    JumpToLine(usize),
+    // Shows or hides the details of the opened file
+    ToggleFileDetails,
+    // Focuses the find bar of the opened file (bound to Ctrl+F)
+    FocusFind,
+    // Scrolls the opened file to the next match of the find bar (bound to Enter)
+    FindNext,
+    // Scrolls the opened file to the previous match of the find bar (bound to Shift+Enter)
+    FindPrevious,
+    // Selects the next shown file node (bound to the Down arrow)
+    SelectNext,
+    // Selects the previous shown file node (bound to the Up arrow)
+    SelectPrevious,
+    // Opens the selected file node (bound to Enter)
+    OpenSelected,
+    // Selects the first shown file node whose name starts with the typed text
+    TypeAhead(String),
+    // Opens or closes the command palette (bound to Ctrl+Shift+P)
+    ToggleCommandPalette,
+    // Closes the command palette (bound to Escape)
+    CloseCommandPalette,
+    // An action for when the text of the command palette changes
+    CommandPaletteQueryChanged(String),
+    // Runs the best match of the command palette
+    RunFirstCommand,
+    // Shows or hides the file tree panel (bound to Ctrl+B)
+    ToggleSidebar,
+    // Shows or hides the quick access section
+    ToggleQuickAccess,
+    // Adds a directory to the bookmarks
+    AddBookmark(String),
+    // Removes a directory from the bookmarks
+    RemoveBookmark(String),
+    // Opens the directory in the menu in a new tab
+    OpenInNewTab(usize),
+    // Shows the tab at the given index
+    SwitchTab(usize),
+    // Closes the tab at the given index
+    CloseTab(usize),
+    // Opens the opened directory again in a new tab (bound to Ctrl+T)
+    NewTab,
+    // Closes the tab currently shown (bound to Ctrl+W)
+    CloseActiveTab,
+    // Makes clicking a folder open it in a new tab
+    SetOpenDirsInNewTab(bool),
+    // An action for when the keyboard modifiers held change
+    ModifiersChanged(keyboard::Modifiers),
+    // Moves the keyboard focus to the next input (bound to F6)
+    FocusNext,
+    // Moves the keyboard focus to the previous input (bound to Shift+F6)
+    FocusPrevious,
+    // An action for when the pointer enters a file in the menu
+    HoverFile(usize),
+    // An action for when the preview of a hovered file was read
+    PreviewLoaded(String, Option<String>),
+    // Shows modification times relative to now, or as dates
+    SetRelativeTimes(bool),
+    // Redraws the relative times, which change as time passes
+    RefreshRelativeTimes,
+    // Shows or hides the thumbnails of the images in the file tree
+    SetShowThumbnails(bool),
+    // An action for when the thumbnail of an image was generated
+    ThumbnailLoaded(ThumbnailKey, Option<image::Handle>),
+    // Shows or hides the number of items in the folders of the file tree
+    SetShowChildCounts(bool),
+    // An action for when the items of the folder at the given path were counted
+    ChildCountLoaded(String, Option<usize>),
+    // An action for when the directory at the given path was read in the background
+    DirLoaded(
+        String,
+        Result<Cancelable<Vec<FileNode>>, (std::io::ErrorKind, String)>,
+    ),
+    // An action for when the directory at the given path turned out to have
+    // more entries than the `large_dir_threshold`, before it was read
+    LargeDirFound(String),
+    // Shows or hides the log panel
+    ToggleLogPanel,
+    // Removes all of the messages from the log panel
+    ClearLog,
+    // Reverses the most recent undoable operation (bound to Ctrl+Z)
+    Undo,
+    // Copies the text to the clipboard
+    CopyToClipboard(String),
+    // Opens the batch rename modal for the files shown in the menu
+    OpenBatchRename,
+    // An action for when the batch rename modal is closed
+    CloseBatchRename,
+    // An action for when the pattern of the batch rename changes
+    BatchRenamePatternChanged(String),
+    // An action for when the text to find in the batch rename changes
+    BatchRenameFindChanged(String),
+    // An action for when the replacement text of the batch rename changes
+    BatchRenameReplaceChanged(String),
+    // Renames the files as previewed in the batch rename modal
+    ApplyBatchRename,
+    // Shows or hides the children of the directory at the index below it
+    ToggleExpanded(usize),
+    // Asks for confirmation to delete the file or folder at the index
+    Delete(usize),
+    // Deletes the file or folder waiting for confirmation
+    ConfirmDelete,
+    // Closes the confirmation without deleting anything
+    CancelDelete,
+    // Shows a field to rename the file or folder at the index in its row
+    StartRename(usize),
+    // Shows a field to rename the selected file or folder
+    RenameSelected,
+    // Lists the opened directory again, picking up changes made outside the application
+    Refresh,
+    // Opens the file node with the external editor
+    OpenExternal(usize),
+    // Remembers the file node to copy it into the folder it is pasted in
+    CopyFile(usize),
+    // Remembers the file node to move it into the folder it is pasted in
+    CutFile(usize),
+    // Copies or moves the file node that was copied or cut into the opened directory
+    Paste,
+    // Opens the file shown in the viewer with the external editor
+    OpenViewedFileExternally,
+    // Changes the command files are opened externally with
+    SetExternalEditor(String),
+    // An action for when the name typed for the renamed file changes
+    SetRenameName(String),
+    // Hides the field to rename a file
+    CancelRename,
+    // Renames the file or folder at the index
+    Rename(usize, String),
+    // Shows the field to name a new folder in the opened directory
+    OpenNewFolder,
+    // An action for when the name of the new folder changes
+    SetNewFolderName(String),
+    // Hides the field to name a new folder
+    CancelNewFolder,
+    // Creates a folder with the name in the opened directory
+    CreateFolder(String),
+    // Changes the number of entries above which opening a directory asks for confirmation
+    SetLargeDirThreshold(usize),
+    // Changes the number of bytes shown at once in the hex viewer
+    SetHexViewWindow(u64),
+    // Shows the bytes of the opened file starting at the given offset in the hex viewer
+    SetHexViewOffset(u64),
+    // Opens the directory waiting for confirmation despite its size
+    LoadLargeDir,
+    // Cancels opening the directory waiting for confirmation
+    CancelLargeDir,
+    // Changes the algorithm used to hash files
+    SetHashAlgorithm(HashAlgorithm),
+    // Hashes the file at the given path
+    ComputeHash(String),
+    // An action for the progress of hashing the file at the given path
+    HashProgress(String, HashEvent),
+    // Opens a folder picker to compare the opened directory against
+    CompareWithFolder,
+    // An action for when the folder to compare against was picked
+    CompareFolderPicked(Option<PathBuf>),
+    // An action for when comparing directories completed
+    ComparisonFinished(Comparison),
+    // Compares the contents of files (instead of their modification time) and compares again
+    SetCompareContents(bool),
+    // An action for when the comparison modal is closed
+    CloseComparison,
+    // Closes the command palette and runs the action of the picked command
+    RunCommand(Box<Action>),
 }
 #[derive(Debug, Clone)]
 pub enum ContextMenuAction {
     OpenFileInfoModal(usize),
+    // Copies the file to a folder chosen with a folder picker
+    CopyTo(usize),
+    // Copies the absolute path of the file to the clipboard
+    CopyPath(usize),
+    // Copies the file name to the clipboard
+    CopyName(usize),
+    // Copies the file name without its extension to the clipboard
+    CopyNameWithoutExtension(usize),
+    // Copies the contents of the file as a base64 data URI to the clipboard
+    CopyAsDataUri(usize),
+    // Extracts the archive into a new folder next to it
+    ExtractHere(usize),
+    // Extracts the archive into a new folder within a folder chosen with a folder picker
+    ExtractTo(usize),
+}
+
+/// Whether a file waiting to be pasted is copied or moved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardOp {
+    /// The file is copied, leaving the original in place
+    Copy,
+    /// The file is moved
+    Cut,
+}
+
+/// Statistics about the text of an opened file
+#[derive(Debug, Clone)]
+pub struct FileStats {
+    /// The number of lines in the file
+    pub lines: usize,
+    /// The number of characters in the file
+    pub characters: usize,
+    /// The name of the language detected for syntax highlighting
+    pub language: String,
 }
 
+/// An image opened in the image viewer
+#[derive(Debug, Clone)]
+pub struct OpenedImage {
+    /// The handle of the image, kept so it is not loaded again on every frame
+    pub handle: image::Handle,
+    /// The width of the image in pixels
+    pub width: u32,
+    /// The height of the image in pixels
+    pub height: u32,
+}
 
 /// The Filters used to search the opened file tree
 #[derive(Debug)]
@@ -75,6 +698,428 @@ pub struct Filters {
     pub file_name_search: String,
     /// The abort handler for the current operation
     pub file_filter_handle: Option<iced::task::Handle>,
+    /// The token cancelling the recursive search in progress
+    pub search_cancel: Option<CancelToken>,
+    /// How the `file_name_search` is matched against file names
+    pub search_mode: SearchMode,
+    /// Whether the search only matches file names with the same case
+    pub case_sensitive: bool,
+    /// Whether a substring search only matches whole words of file names
+    pub whole_word: bool,
+    /// The regular expression compiled from the last regex search
+    pub name_regex: Option<NameRegex>,
+    /// Whether only folders or only files are shown
+    pub kind: KindFilter,
+    /// Whether the search also matches the files within the subfolders of the `opened_dir`
+    pub recursive: bool,
+    /// Whether hidden files (those whose name starts with a dot) are shown
+    pub show_hidden: bool,
+    /// The text typed into the content search
+    pub content_search: String,
+    /// The paths of the files whose contents matched the last content search
+    /// (`None` when no content search is applied)
+    pub content_matches: Option<HashSet<String>>,
+}
+
+/// The state of a directory tab, kept while another tab is shown
+#[derive(Debug, Clone)]
+pub struct Tab {
+    /// The directory opened in the tab
+    pub opened_dir: FileNode,
+    /// The file nodes listed in the tab
+    pub files: Vec<FileNode>,
+    /// The text of the file name search
+    pub file_name_search: String,
+    /// Whether only folders or only files are shown
+    pub kind: KindFilter,
+    /// How the search is matched against file names
+    pub search_mode: SearchMode,
+    /// The index of the selected file node
+    pub selected_index: Option<usize>,
+    /// The page of the file tree shown
+    pub page: usize,
+    /// A flag set when `files` holds the results of a recursive search
+    pub showing_search_results: bool,
+    /// A flag set when the recursive search stopped at its maximum number of results
+    pub search_results_truncated: bool,
+    /// A flag set when the tab was left while its directory was being read
+    pub loading: bool,
+    /// The file shown in the viewer of the tab, if any
+    pub opened_file: Option<FileNode>,
+    /// How the viewer of the tab presents its file
+    pub opened_file_view: ViewPreferences,
+}
+
+/// A listing of the opened directory being read in the background
+#[derive(Debug)]
+pub struct PendingDirLoad {
+    /// The absolute path of the directory being read
+    pub path: String,
+    /// The token cancelling the read, once it has started
+    pub cancel: Option<CancelToken>,
+    /// The absolute path of the file node to select once the directory is read
+    pub select: Option<String>,
+    /// The number of entries above which reading asks for confirmation first,
+    /// if it should
+    pub entry_limit: Option<usize>,
+    /// The directory opened before, returned to if reading is not confirmed
+    pub previous: Option<FileNode>,
+}
+
+/// A directory waiting for confirmation to be read, as it has more entries
+/// than the `large_dir_threshold`
+#[derive(Debug)]
+pub struct LargeDirPrompt {
+    /// The directory, already opened with an empty listing
+    pub dir: FileNode,
+    /// The read of the directory, resumed once confirmed
+    pub load: PendingDirLoad,
+}
+
+/// Tab methods
+impl Tab {
+    /// Creates a tab for a directory that was not listed yet
+    ///
+    /// # Arguments
+    ///
+    /// * `opened_dir` - The directory opened in the tab
+    pub fn new(opened_dir: FileNode) -> Tab {
+        Tab {
+            opened_dir,
+            files: Vec::new(),
+            file_name_search: String::new(),
+            kind: KindFilter::default(),
+            search_mode: SearchMode::default(),
+            selected_index: None,
+            page: 0,
+            showing_search_results: false,
+            search_results_truncated: false,
+            loading: false,
+            opened_file: None,
+            opened_file_view: ViewPreferences::default(),
+        }
+    }
+}
+
+/// The filters remembered for a directory, restored when it is opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DirViewPreferences {
+    /// Whether only folders or only files are shown
+    pub kind: KindFilter,
+    /// How searches are matched against file names
+    pub search_mode: SearchMode,
+    /// The order the file tree is sorted in
+    pub sort_mode: SortMode,
+}
+
+/// How much space each row of the file tree takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RowDensity {
+    /// Smaller text and less padding, fitting more rows on screen
+    Compact,
+    /// Large text and padding
+    #[default]
+    Comfortable,
+}
+
+/// Row Density methods
+impl RowDensity {
+    /// All of the densities, in the order they are shown to the user
+    pub const ALL: [RowDensity; 2] = [RowDensity::Compact, RowDensity::Comfortable];
+
+    /// The font size of the file names
+    pub fn font_size(&self) -> f32 {
+        match self {
+            RowDensity::Compact => 16.0,
+            RowDensity::Comfortable => 24.0,
+        }
+    }
+
+    /// The vertical padding of each row
+    pub fn padding(&self) -> f32 {
+        match self {
+            RowDensity::Compact => 1.0,
+            RowDensity::Comfortable => 5.0,
+        }
+    }
+}
+
+impl std::fmt::Display for RowDensity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RowDensity::Compact => write!(f, "Compact"),
+            RowDensity::Comfortable => write!(f, "Comfortable"),
+        }
+    }
+}
+
+/// How far the children of an expanded directory are indented in the file tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TreeIndent {
+    /// Fits deeply nested directories on screen
+    Narrow,
+    /// One level per expander width
+    #[default]
+    Normal,
+    /// Makes the nesting easier to follow
+    Wide,
+}
+
+/// Tree Indent methods
+impl TreeIndent {
+    /// All of the indents, in the order they are shown to the user
+    pub const ALL: [TreeIndent; 3] = [TreeIndent::Narrow, TreeIndent::Normal, TreeIndent::Wide];
+
+    /// The width (in pixels) of each level of nesting
+    pub fn width(&self) -> f32 {
+        match self {
+            TreeIndent::Narrow => 8.0,
+            TreeIndent::Normal => 16.0,
+            TreeIndent::Wide => 28.0,
+        }
+    }
+}
+
+impl std::fmt::Display for TreeIndent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeIndent::Narrow => write!(f, "Narrow"),
+            TreeIndent::Normal => write!(f, "Normal"),
+            TreeIndent::Wide => write!(f, "Wide"),
+        }
+    }
+}
+
+/// Whether the application follows the color mode of the system or overrides it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorMode {
+    /// Light or dark, as the system is
+    #[default]
+    System,
+    /// Always light
+    Light,
+    /// Always dark
+    Dark,
+}
+
+/// Color Mode methods
+impl ColorMode {
+    /// All of the color modes, in the order they are shown to the user
+    pub const ALL: [ColorMode; 3] = [ColorMode::System, ColorMode::Light, ColorMode::Dark];
+}
+
+impl std::fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorMode::System => write!(f, "System"),
+            ColorMode::Light => write!(f, "Light"),
+            ColorMode::Dark => write!(f, "Dark"),
+        }
+    }
+}
+
+/// Restricts the file tree to folders or files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KindFilter {
+    /// Show both folders and files
+    #[default]
+    All,
+    /// Only show folders
+    FoldersOnly,
+    /// Only show files
+    FilesOnly,
+}
+
+/// The ways a search can be matched against file names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SearchMode {
+    /// The file name contains the search
+    #[default]
+    Substring,
+    /// The characters of the search appear in order in the file name
+    Fuzzy,
+    /// The search is a regular expression matching the file name
+    Regex,
+}
+
+/// A regular expression compiled from the file name search. It is kept so the
+/// search is only compiled again when it changes.
+#[derive(Debug, Clone)]
+pub struct NameRegex {
+    /// The search the regex was compiled from
+    pub pattern: String,
+    /// Whether the regex was compiled to match the case
+    pub case_sensitive: bool,
+    /// The compiled regex, or the error compiling the search
+    pub compiled: Result<Regex, String>,
+}
+
+/// A file name search, prepared to be matched against many file names
+#[derive(Debug, Clone)]
+pub struct NameQuery {
+    /// The trimmed search, lowercased unless the search is case-sensitive
+    pub search: String,
+    /// Whether file names must have the same case as the search
+    pub case_sensitive: bool,
+    /// Whether the search must be a whole word of the file name
+    pub whole_word: bool,
+    /// The compiled search of a regex search (`None` for other searches, or
+    /// when the search is not a valid regex)
+    pub regex: Option<Regex>,
+}
+
+impl NameQuery {
+    /// Returns true if the file name contains the search
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The query instance
+    /// * `name` - The file name to match
+    pub fn matches(&self, name: &str) -> bool {
+        self.find(&self.cased(name)).is_some()
+    }
+
+    /// Returns the indices of the characters of the file name matching the
+    /// search, or `None` if it doesn't match
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The query instance
+    /// * `name` - The file name to match
+    pub fn matched_indices(&self, name: &str) -> Option<Vec<usize>> {
+        let cased = self.cased(name);
+        let range = self.find(&cased)?;
+        // The match is found in the lowercased name, whose characters line up
+        // with the original name unless lowercasing changed their number
+        if cased.chars().count() != name.chars().count() {
+            return Some(Vec::new());
+        }
+        Some(char_indices(&cased, range))
+    }
+
+    /// Returns the file name lowercased, unless the search is case-sensitive
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The query instance
+    /// * `name` - The file name to match
+    fn cased<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if self.case_sensitive {
+            Cow::Borrowed(name)
+        } else {
+            Cow::Owned(name.to_lowercase())
+        }
+    }
+
+    /// Returns the byte range of the first match of the search
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The query instance
+    /// * `name` - The file name to match, already lowercased if needed
+    fn find(&self, name: &str) -> Option<Range<usize>> {
+        // Words are separated by anything but letters and digits, such as the
+        // dots, dashes and underscores of file names
+        let is_word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+        name.match_indices(&self.search)
+            .find(|(start, found)| {
+                !self.whole_word
+                    || (!is_word(name[..*start].chars().next_back())
+                        && !is_word(name[start + found.len()..].chars().next()))
+            })
+            .map(|(start, found)| start..start + found.len())
+    }
+}
+
+/// Returns the indices of the characters within a byte range of a text
+///
+/// # Arguments
+///
+/// * `text` - The text the range is in
+/// * `range` - The byte range, on character boundaries
+fn char_indices(text: &str, range: Range<usize>) -> Vec<usize> {
+    let start = text[..range.start].chars().count();
+    let count = text[range].chars().count();
+    (start..start + count).collect()
+}
+
+/// Returns the matcher of fuzzy searches. Searches are lowercased unless they
+/// are case-sensitive, and the default (smart case) matcher ignores the case of
+/// lowercase searches.
+///
+/// # Arguments
+///
+/// * `case_sensitive` - Whether file names must have the same case as the search
+fn fuzzy_matcher(case_sensitive: bool) -> SkimMatcherV2 {
+    if case_sensitive {
+        SkimMatcherV2::default().respect_case()
+    } else {
+        SkimMatcherV2::default()
+    }
+}
+
+/// The hash of a file, computed on demand
+#[derive(Debug, Clone)]
+pub struct FileHash {
+    /// The path of the hashed file
+    pub path: String,
+    /// The algorithm the file is hashed with
+    pub algorithm: HashAlgorithm,
+    /// The bytes hashed so far and the total bytes of the file
+    pub progress: (u64, u64),
+    /// The hex digest of the file, or the error that occurred while hashing it
+    /// (`None` while hashing)
+    pub digest: Option<Result<String, String>>,
+}
+
+/// The state of the batch rename modal
+#[derive(Debug)]
+pub struct BatchRename {
+    /// The files to rename, in the order used for the `{n}` placeholder
+    pub sources: Vec<PathBuf>,
+    /// The pattern of the new names
+    pub pattern: String,
+    /// The text to replace in the new names
+    pub find: String,
+    /// The text replacing `find`
+    pub replace: String,
+    /// The renames resulting from the pattern, previewed before applying them
+    pub preview: Vec<PlannedRename>,
+}
+
+/// Batch Rename methods
+impl BatchRename {
+    /// Plans the renames again after the pattern changed
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The batch rename state
+    pub fn update_preview(&mut self) {
+        self.preview = plan_batch_rename(&self.sources, &self.pattern, &self.find, &self.replace);
+    }
+}
+
+/// The state of the address bar
+#[derive(Debug, Default)]
+pub struct AddressBar {
+    /// The path typed into the address bar
+    pub input: String,
+    /// The candidates from the last Tab-completion
+    pub completions: Vec<String>,
+    /// The index of the completion currently shown when cycling with Tab
+    pub completion_index: Option<usize>,
+    /// Why the last path submitted could not be opened (if it couldn't)
+    pub error: Option<String>,
+    /// Whether the address bar is the input typed into last. Tab only completes
+    /// the address then, so it does nothing to the address from other inputs.
+    pub focused: bool,
 }
 
 #[derive(Debug)]
@@ -89,37 +1134,70 @@ pub struct Highlighting {
     pub theme_set: syntect::highlighting::ThemeSet,
 }
 
+/// The methods used to start the application
+impl FileExplorerApp {
+    /// Creates the application. If a path is passed as the first command-line
+    /// argument, the application navigates to it.
+    pub fn new() -> Self {
+        let mut app = FileExplorerApp::default();
+
+        if let Some(path) = std::env::args().nth(1)
+            && let Err(e) = app.navigate_to_file(&path)
+        {
+            log::error!("Could not open {}: {}", path, e);
+        }
+
+        app
+    }
+}
+
 /// The default methods
 impl Default for FileExplorerApp {
     fn default() -> Self {
         let cwd = canonicalize(Path::new("./"));
 
         if cwd.is_err() {
-            eprintln!("Could not open CWD: {}", cwd.err().unwrap());
+            log::error!("Could not open CWD: {}", cwd.err().unwrap());
             exit(1);
         }
 
-        let cwd_absolute_path = &String::from(cwd.unwrap().to_str().unwrap());
+        #[allow(unused_mut)]
+        let mut start_dir = String::from(cwd.unwrap().to_str().unwrap());
+
+        // Start in the directory opened last, if it still exists
+        #[cfg(feature = "serde")]
+        let settings = Settings::load();
+        #[cfg(feature = "serde")]
+        if let Some(last_dir) = settings
+            .last_dir
+            .as_ref()
+            .filter(|dir| Path::new(dir).is_dir())
+        {
+            start_dir = last_dir.clone();
+        }
+        let cwd_absolute_path = &start_dir;
 
         // Read the Current Working Directory to build the initial Tree Menu
         let nodes: Vec<FileNode> = match read_dir(cwd_absolute_path) {
             Ok(p) => p,
             Err(e) => {
-                eprintln!("Error: {}", e);
+                log::error!("Error: {}", e);
                 let s: Vec<FileNode> = Vec::new();
                 s
             }
         };
 
         // A referencee to the opened directory
-        let opened_dir = FileNode::from_relative_path(cwd_absolute_path);
+        let opened_dir = FileNode::from_relative_path(cwd_absolute_path)
+            .ok()
+            .unwrap();
 
         let system_color_mode = match dark_light::detect() {
             Ok(mode) => mode,
             Err(_) => dark_light::Mode::Light,
         };
 
-        println!("Detected system color mode: {:?}", system_color_mode);
+        log::info!("Detected system color mode: {:?}", system_color_mode);
 
         let panes = pane_grid::State::with_configuration(pane_grid::Configuration::Split {
             axis: pane_grid::Axis::Vertical,
@@ -128,25 +1206,136 @@ impl Default for FileExplorerApp {
             b: Box::new(pane_grid::Configuration::Pane(PaneContent::Content)),
         });
 
-        FileExplorerApp {
+        #[allow(unused_mut)]
+        let mut app = FileExplorerApp {
             files: nodes,
-            opened_dir: opened_dir.ok().unwrap(),
+            disk_space: disk_space(cwd_absolute_path),
+            selected_index: None,
+            page: 0,
+            page_size: DEFAULT_PAGE_SIZE,
+            opened_dir: opened_dir.clone(),
             opened_file: None,
             opened_file_contents: Ok(String::from("")),
             opened_file_type: None,
+            opened_file_behavior: OpenBehavior::InternalText,
+            opened_file_view: ViewPreferences::default(),
+            find_query: String::from(""),
+            find_matches: Vec::new(),
+            find_current: None,
+            opened_file_details: None,
+            show_file_details: false,
+            opened_file_stats: None,
+            opened_image: None,
+            opened_file_encoding: None,
+            opened_file_read_limit: TEXT_VIEW_CHUNK_BYTES,
+            opened_file_truncated: false,
+            opened_file_binary: false,
             filters: Filters {
                 file_name_search: String::from(""),
                 file_filter_handle: None,
+                search_cancel: None,
+                search_mode: SearchMode::default(),
+                case_sensitive: false,
+                whole_word: false,
+                name_regex: None,
+                kind: KindFilter::default(),
+                recursive: false,
+                show_hidden: false,
+                content_search: String::new(),
+                content_matches: None,
             },
+            max_search_results: DEFAULT_MAX_SEARCH_RESULTS,
+            showing_search_results: false,
+            search_results_truncated: false,
             system_color_mode,
+            color_mode: ColorMode::default(),
+            wrap_mode: WrapMode::default(),
             panes,
+            sidebar_visible: true,
+            log_panel_open: false,
+            history: vec![opened_dir.absolute_path.clone()],
+            history_index: 0,
+            tabs: vec![Tab::new(opened_dir)],
+            active_tab: 0,
+            open_dirs_in_new_tab: false,
+            modifiers: keyboard::Modifiers::default(),
+            quick_access_open: true,
+            places: quick_access_places(),
+            recent_files: RecentFiles::load(),
+            bookmarks: Vec::new(),
+            previews: HashMap::new(),
+            dir_views: HashMap::new(),
+            default_dir_view: DirViewPreferences::default(),
+            relative_times: true,
+            show_thumbnails: false,
+            thumbnails: ThumbnailCache::default(),
+            show_child_counts: false,
+            child_counts: HashMap::new(),
+            resolve_symlinks: true,
+            follow_symlinks: false,
             highlighting: Highlighting {
                 syntax_set: SyntaxSet::load_defaults_newlines(),
                 theme_set: ThemeSet::load_defaults(),
             },
+            highlighted: None,
             file_info_modal_node: None,
             file_info_modal_open: false,
-        }
+            file_info_dir_size: None,
+            file_info_dir_size_cancel: None,
+            max_walk_depth: DEFAULT_MAX_WALK_DEPTH,
+            address_bar: AddressBar {
+                input: cwd_absolute_path.clone(),
+                ..AddressBar::default()
+            },
+            associations: FileAssociations::default(),
+            associations_modal_open: false,
+            settings_modal_open: false,
+            row_density: RowDensity::default(),
+            tree_indent: TreeIndent::default(),
+            extension_display: ExtensionDisplay::default(),
+            sort_mode: SortMode::default(),
+            show_whitespace: false,
+            show_line_numbers: true,
+            syntax_theme: SyntaxTheme::default(),
+            new_association_extension: String::from(""),
+            status_message: None,
+            copy_progress: None,
+            extract_progress: None,
+            undo_stack: Vec::new(),
+            clipboard: None,
+            selection: HashSet::new(),
+            type_ahead: String::new(),
+            type_ahead_at: None,
+            new_folder_name: None,
+            renaming: None,
+            command_palette_open: false,
+            command_palette_query: String::from(""),
+            batch_rename: None,
+            large_dir_threshold: DEFAULT_LARGE_DIR_THRESHOLD,
+            hex_view_window: DEFAULT_HEX_VIEW_WINDOW,
+            hex_view_offset: 0,
+            large_dir_prompt: None,
+            pending_dir_load: None,
+            dir_error: None,
+            file_tree_offset: 0.0,
+            dir_scroll_offsets: HashMap::new(),
+            pending_scroll: None,
+            pending_delete: Vec::new(),
+            hash_algorithm: HashAlgorithm::default(),
+            file_hash: None,
+            file_hash_handle: None,
+            comparison: None,
+            compare_contents: false,
+            external_editor: String::new(),
+            window_size: None,
+            #[cfg(feature = "serde")]
+            saved_settings: Settings::default(),
+        };
+
+        #[cfg(feature = "serde")]
+        app.apply_settings(settings);
+
+        app
     }
 }
 
@@ -159,16 +1348,85 @@ impl FileExplorerApp {
     /// * `self` - the application instance
     /// * `action` - the [`Action`] that occurred during the last frame
     pub fn post_update(&mut self, action: Action) -> Task<Action> {
+        // Typing into another input (or moving the focus) leaves the address bar
+        if matches!(
+            action,
+            Action::SetRenameName(_)
+                | Action::DebouncedSearch(_)
+                | Action::SetContentSearch(_)
+                | Action::SetNewFolderName(_)
+                | Action::FindQueryChanged(_)
+                | Action::CommandPaletteQueryChanged(_)
+                | Action::SetExternalEditor(_)
+                | Action::BatchRenamePatternChanged(_)
+                | Action::BatchRenameFindChanged(_)
+                | Action::BatchRenameReplaceChanged(_)
+                | Action::NewAssociationExtensionChanged(_)
+                | Action::FocusNext
+                | Action::FocusPrevious
+                | Action::FocusFind
+                | Action::OpenFile(_)
+                | Action::SelectNext
+                | Action::SelectPrevious
+                | Action::TypeAhead(_)
+        ) {
+            self.address_bar.focused = false;
+        }
+
         let opened_dir = &self.opened_dir;
         match action {
             // Runs when a file node in the tree is clicked
             Action::OpenFile(node) => {
+                let Some(path) = self.files.get(node).map(|f| f.absolute_path.clone()) else {
+                    return Task::none();
+                };
+
+                // Ctrl+click adds the node to the selection, or removes it
+                if self.modifiers.command() {
+                    if self.selection.is_empty()
+                        && let Some(selected) = self.selected_index.and_then(|i| self.files.get(i))
+                    {
+                        self.selection.insert(selected.absolute_path.clone());
+                    }
+                    if !self.selection.remove(&path) {
+                        self.selection.insert(path);
+                    }
+                    self.selected_index = Some(node);
+                    return Task::none();
+                }
+
+                // Shift+click selects the shown nodes between the last clicked one and this one
+                if self.modifiers.shift()
+                    && let Some(anchor) = self.selected_index
+                {
+                    let visible: Vec<usize> =
+                        self.visible_files().into_iter().map(|(i, _)| i).collect();
+                    if let (Some(start), Some(end)) = (
+                        visible.iter().position(|&i| i == anchor),
+                        visible.iter().position(|&i| i == node),
+                    ) {
+                        self.selection = visible[start.min(end)..=start.max(end)]
+                            .iter()
+                            .map(|&i| self.files[i].absolute_path.clone())
+                            .collect();
+                        return Task::none();
+                    }
+                }
+
+                self.selection.clear();
+                // Folders open in a new tab if so configured
+                if self.open_dirs_in_new_tab && self.files[node].is_dir {
+                    return self.post_update(Action::OpenInNewTab(node));
+                }
+
+                self.selected_index = Some(node);
+
                 match self.open_child_file(node) {
                     Ok(_) => {
-                        println!("Successfully opened file")
+                        log::debug!("Successfully opened file")
                     }
                     Err(e) => {
-                        eprintln!("Error: {}", e)
+                        log::error!("Error: {}", e)
                     }
                 }
                 Task::none()
@@ -176,20 +1434,53 @@ impl FileExplorerApp {
             // Runs when the close file button is clicked
             Action::CloseFile => {
                 self.opened_file = None;
+                self.opened_file_details = None;
                 self.opened_file_contents = Ok(String::from(""));
                 self.opened_file_type = None;
+                self.opened_file_binary = false;
+                self.opened_image = None;
+                self.hex_view_offset = 0;
+                self.highlighted = None;
+                self.opened_file_behavior = OpenBehavior::InternalText;
+                self.opened_file_view = ViewPreferences::default();
+                self.opened_file_stats = None;
+                self.opened_file_encoding = None;
+                self.find_matches.clear();
+                Task::none()
+            }
+            Action::HistoryBack => {
+                if self.history_index > 0 {
+                    self.visit_history(self.history_index - 1);
+                }
+                Task::none()
+            }
+            Action::HistoryForward => {
+                if self.history_index + 1 < self.history.len() {
+                    self.visit_history(self.history_index + 1);
+                }
                 Task::none()
             }
             // Runs when the top level `../` button is clicked
             Action::GoBack() => {
+                // Directories within an archive go back up within the archive
+                if let FileSource::Archive {
+                    archive_path,
+                    entry_path,
+                } = &opened_dir.source
+                    && let Some(parent_node) = archive_parent(archive_path, entry_path)
+                {
+                    let _ = self.open_file(parent_node);
+                    return Task::none();
+                }
+
                 match &opened_dir.parent_folder {
                     Some(parent) => {
-                        let parent_node = FileNode::from_relative_path(parent);
+                        let parent_node = FileNode::from_path(parent, self.resolve_symlinks);
                         let _ = self.open_file(parent_node.expect("Could not read parent file"));
                     }
                     None => {
                         // Do nothing
-                        println!("Could not find parent folder...")
+                        log::warn!("Could not find parent folder...")
                     }
                 }
                 Task::none()
@@ -208,7 +1499,7 @@ impl FileExplorerApp {
 
                 // Create a task that performs the search after a delay
                 let handler =
-                    Task::perform(tokio::time::sleep(Duration::from_millis(500)), move |_| {
+                    Task::perform(tokio::time::sleep(SEARCH_DEBOUNCE_INTERVAL), move |_| {
                         Action::SearchByFilename(search_file_name)
                     });
 
@@ -222,89 +1513,3234 @@ impl FileExplorerApp {
                 task_handler
             }
             Action::SearchByFilename(search_file_name) => {
-                println!("Searching for [{}]", search_file_name);
+                log::debug!("Searching for [{}]", search_file_name);
+
+                self.filters.file_name_search = search_file_name;
+
+                if self.filters.recursive && !self.filters.file_name_search.trim().is_empty() {
+                    return self.search_recursively();
+                }
+
+                // Stop any recursive search still running for a previous name
+                if let Some(search) = self.filters.search_cancel.take() {
+                    search.cancel();
+                }
 
-                for file in &mut self.files {
-                    file.matches_filters = file
-                        .file_name
-                        .to_lowercase()
-                        .contains(&search_file_name.trim().to_lowercase());
+                // Go back to the children of the opened directory
+                if self.showing_search_results {
+                    self.reload_files(None);
                 }
+                self.apply_filters();
 
                 Task::none()
             }
-            // Runs when the panes are resized
-            Action::PanesResized(event) => {
-                self.panes.resize(event.split, event.ratio);
+            Action::SetContentSearch(content_search) => {
+                self.filters.content_search = content_search;
                 Task::none()
             }
-            Action::OpenContextMenu(context_menu_action) => {
-                match context_menu_action {
-                    ContextMenuAction::OpenFileInfoModal(index) => {
-                        println!("Opening File Info Model for file at index: {}", index);
-                        let file_node = self.files.get(index).cloned();
-                        self.file_info_modal_node = file_node;
-                        self.file_info_modal_open = true;
-                    }
+            Action::SearchByContent(term) => {
+                let term = term.trim().to_lowercase();
+                if term.is_empty() {
+                    self.filters.content_matches = None;
+                    self.apply_filters();
+                    return Task::none();
+                }
+
+                let paths: Vec<String> = self
+                    .files
+                    .iter()
+                    .filter(|f| !f.is_dir && f.source == FileSource::Disk)
+                    .map(|f| f.absolute_path.clone())
+                    .collect();
+                self.status_message = Some(format!("Searching {} files…", paths.len()));
+
+                Task::perform(
+                    tokio::task::spawn_blocking(move || {
+                        let matches = paths
+                            .into_iter()
+                            .filter(|path| file_contains(path, &term))
+                            .collect();
+                        (term, matches)
+                    }),
+                    |result| match result {
+                        Ok((term, matches)) => Action::ContentSearchFinished(term, matches),
+                        Err(e) => {
+                            log::error!("Could not search the file contents: {}", e);
+                            Action::ContentSearchFinished(String::new(), Vec::new())
+                        }
+                    },
+                )
+            }
+            Action::ContentSearchFinished(term, matches) => {
+                // Ignore results for a search that was changed since
+                if term != self.filters.content_search.trim().to_lowercase() {
+                    return Task::none();
                 }
+
+                self.status_message = Some(match matches.len() {
+                    1 => format!("1 file contains \"{}\"", term),
+                    count => format!("{} files contain \"{}\"", count, term),
+                });
+                self.filters.content_matches = Some(matches.into_iter().collect());
+                self.apply_filters();
                 Task::none()
             }
-            Action::CloseFileInfoModal => {
-                self.file_info_modal_open = false;
-                self.file_info_modal_node = None;
+            Action::SetRecursiveSearch(recursive) => {
+                self.filters.recursive = recursive;
+                self.post_update(Action::SearchByFilename(
+                    self.filters.file_name_search.clone(),
+                ))
+            }
+            Action::SetMaxSearchResults(max_results) => {
+                self.max_search_results = max_results;
                 Task::none()
             }
-        }
-    }
-
-    fn open_child_file(&mut self, index: usize) -> Result<(), std::io::Error> {
-        let file = &self.files[index];
-        self.open_file(file.clone())
-    }
-
-    /// Opens a file or directory. This will set `opened_file` or `opened_dir` based on the file type.
-    ///
-    /// # Arguments
-    ///
-    /// * `self` - The application instancee
-    /// * `file` - The file that should be opened from the file tree. Can be a `File` or `Directory` node.
-    fn open_file(&mut self, file: FileNode) -> Result<(), std::io::Error> {
-        println!("Attempting to open, {:?}", file);
-
-        if let Some(f) = &self.opened_file
-            && f.absolute_path == file.absolute_path
-        {
-            println!("File is already opened - skipping");
-            return Ok(());
-        }
-
-        let opened_file = file.clone();
-        let absolute_path = opened_file.absolute_path.clone();
+            Action::SetResolveSymlinks(resolve_symlinks) => {
+                self.resolve_symlinks = resolve_symlinks;
+                Task::none()
+            }
+            Action::SetFollowSymlinks(follow_symlinks) => {
+                self.follow_symlinks = follow_symlinks;
+                Task::none()
+            }
+            Action::RecursiveSearchFinished(search_file_name, results) => {
+                // Ignore results for a search that was changed since
+                let Cancelable::Completed(results) = results else {
+                    return Task::none();
+                };
+                if !self.filters.recursive || search_file_name != self.filters.file_name_search {
+                    return Task::none();
+                }
+                self.filters.search_cancel = None;
 
-        if opened_file.is_dir {
-            self.filters.file_name_search.clear();
-            match read_dir(&absolute_path) {
-                Err(e) => {
-                    eprintln!("Could not open file: {}", e);
+                self.files = results.nodes;
+                sort_nodes(&mut self.files, self.sort_mode);
+                self.selected_index = None;
+                self.showing_search_results = true;
+                self.search_results_truncated = results.truncated;
+                self.apply_filters();
+                Task::none()
+            }
+            Action::SetSearchMode(mode) => {
+                self.filters.search_mode = mode;
+                self.store_dir_view();
+                if self.showing_search_results {
+                    return self.search_recursively();
                 }
-                Ok(v) => {
-                    self.opened_dir = opened_file;
-                    self.files = v;
+                self.apply_filters();
+                Task::none()
+            }
+            Action::SetCaseSensitive(case_sensitive) => {
+                self.filters.case_sensitive = case_sensitive;
+                if self.showing_search_results {
+                    return self.search_recursively();
                 }
+                self.apply_filters();
+                Task::none()
             }
-        } else {
-            self.opened_file = Some(opened_file);
-            self.opened_file_contents = fs::read_to_string(&file.absolute_path);
-
-            match &self.opened_file_contents {
-                // Ignore errors when reading file contents
-                Err(_) => {}
-                Ok(_) => {
-                    self.opened_file_type = determine_file_type(&file.absolute_path);
+            Action::SetWholeWord(whole_word) => {
+                self.filters.whole_word = whole_word;
+                if self.showing_search_results {
+                    return self.search_recursively();
+                }
+                self.apply_filters();
+                Task::none()
+            }
+            Action::SetKindFilter(kind) => {
+                self.filters.kind = kind;
+                self.store_dir_view();
+                self.apply_filters();
+                Task::none()
+            }
+            Action::SetShowHidden(show_hidden) => {
+                self.filters.show_hidden = show_hidden;
+                self.apply_filters();
+                Task::none()
+            }
+            Action::RememberDirView(remember) => {
+                let path = self.opened_dir.absolute_path.clone();
+                if remember {
+                    self.dir_views.insert(path, self.current_dir_view());
+                } else {
+                    self.dir_views.remove(&path);
+                    // Go back to the filters of the other directories
+                    self.apply_dir_view(self.default_dir_view);
+                    self.collapse_all();
+                    sort_nodes(&mut self.files, self.sort_mode);
+                    self.apply_filters();
                 }
+                Task::none()
             }
-        }
+            Action::ApplyDirViewToAll => {
+                self.default_dir_view = self.current_dir_view();
+                self.dir_views.clear();
+                self.status_message = Some(String::from("Applied the filters to all folders"));
+                Task::none()
+            }
+            // Runs when the panes are resized
+            Action::PanesResized(event) => {
+                self.panes.resize(event.split, event.ratio);
+                Task::none()
+            }
+            Action::WindowResized(size) => {
+                self.window_size = Some((size.width, size.height));
+                Task::none()
+            }
+            Action::OpenContextMenu(context_menu_action) => match context_menu_action {
+                ContextMenuAction::OpenFileInfoModal(index) => {
+                    log::debug!("Opening File Info Model for file at index: {}", index);
+                    let file_node = self.files.get(index).cloned();
+                    self.file_info_modal_open = true;
+                    self.file_info_dir_size = None;
+                    self.cancel_dir_size();
 
-        Ok(())
+                    let task = match &file_node {
+                        // Measure the directory off the UI thread
+                        Some(node) if node.is_dir => {
+                            let path = node.absolute_path.clone();
+                            let max_depth = self.max_walk_depth;
+                            let follow_symlinks = self.follow_symlinks;
+                            let cancel = CancelToken::default();
+                            self.file_info_dir_size_cancel = Some(cancel.clone());
+                            Task::perform(
+                                tokio::task::spawn_blocking(move || {
+                                    let size =
+                                        directory_size(&path, max_depth, follow_symlinks, &cancel);
+                                    (path, size)
+                                }),
+                                |result| match result {
+                                    Ok((path, Cancelable::Completed((size, limited)))) => {
+                                        Action::DirectorySizeComputed(path, size, limited)
+                                    }
+                                    // The modal was closed or shows another file, so
+                                    // the result would be ignored anyway
+                                    Ok((_, Cancelable::Cancelled)) => {
+                                        Action::DirectorySizeComputed(String::new(), 0, false)
+                                    }
+                                    Err(e) => {
+                                        log::error!("Could not compute directory size: {}", e);
+                                        Action::DirectorySizeComputed(String::new(), 0, false)
+                                    }
+                                },
+                            )
+                        }
+                        _ => Task::none(),
+                    };
+
+                    self.file_info_modal_node = file_node;
+                    task
+                }
+                ContextMenuAction::ExtractHere(index) => {
+                    let Some(file) = self.files.get(index) else {
+                        return Task::none();
+                    };
+                    let Some(folder) = file.parent_folder.clone() else {
+                        return Task::none();
+                    };
+                    let archive = file.absolute_path.clone();
+                    self.extract_archive(archive, Path::new(&folder))
+                }
+                ContextMenuAction::ExtractTo(index) => {
+                    let Some(file) = self.files.get(index) else {
+                        return Task::none();
+                    };
+                    let archive = file.absolute_path.clone();
+                    Task::perform(
+                        pick_folder(self.opened_dir.absolute_path.clone()),
+                        move |folder| Action::ExtractToFolderPicked(archive, folder),
+                    )
+                }
+                ContextMenuAction::CopyTo(index) => {
+                    let files = self.selected_or(index);
+                    if files.iter().any(|f| f.is_dir) {
+                        self.status_message = Some(String::from("Only files can be copied"));
+                        return Task::none();
+                    }
+                    if files.iter().any(|f| f.source != FileSource::Disk) {
+                        self.status_message =
+                            Some(String::from("Files within archives cannot be copied yet"));
+                        return Task::none();
+                    }
+
+                    let sources: Vec<String> = files.into_iter().map(|f| f.absolute_path).collect();
+                    Task::perform(
+                        pick_folder(self.opened_dir.absolute_path.clone()),
+                        move |folder| Action::CopyToFolderPicked(sources, folder),
+                    )
+                }
+                ContextMenuAction::CopyPath(index) => match self.files.get(index) {
+                    Some(file) => self.copy_to_clipboard(file.absolute_path.clone()),
+                    None => Task::none(),
+                },
+                ContextMenuAction::CopyName(index) => match self.files.get(index) {
+                    Some(file) => self.copy_to_clipboard(file.file_name.clone()),
+                    None => Task::none(),
+                },
+                ContextMenuAction::CopyNameWithoutExtension(index) => match self.files.get(index) {
+                    Some(file) => {
+                        let stem = Path::new(&file.file_name)
+                            .file_stem()
+                            .and_then(|s| s.to_str())
+                            .unwrap_or(&file.file_name)
+                            .to_string();
+                        self.copy_to_clipboard(stem)
+                    }
+                    None => Task::none(),
+                },
+                ContextMenuAction::CopyAsDataUri(index) => {
+                    let Some(file) = self.files.get(index) else {
+                        return Task::none();
+                    };
+                    if file.is_dir {
+                        self.status_message =
+                            Some(String::from("Only files can be copied as a data URI"));
+                        return Task::none();
+                    }
+
+                    // Read one byte past the limit to tell if the file is too large
+                    match read_bytes(file, DATA_URI_MAX_SIZE + 1) {
+                        Ok(bytes) if bytes.len() as u64 > DATA_URI_MAX_SIZE => {
+                            self.status_message = Some(format!(
+                                "{} is too large to copy as a data URI (the limit is {})",
+                                file.file_name,
+                                human_size(DATA_URI_MAX_SIZE)
+                            ));
+                            Task::none()
+                        }
+                        Ok(bytes) => {
+                            let uri = data_uri(&bytes, mime_type(&file.absolute_path));
+                            // The URI itself is too long for the status bar
+                            self.status_message =
+                                Some(format!("Copied {} as a data URI", file.file_name));
+                            iced::clipboard::write(uri)
+                        }
+                        Err(e) => {
+                            log::error!("Could not read {}: {}", file.absolute_path, e);
+                            Task::none()
+                        }
+                    }
+                }
+            },
+            Action::CopyToClipboard(value) => self.copy_to_clipboard(value),
+            Action::OpenBatchRename => {
+                // The selected files are renamed, or every shown file if none are selected
+                let sources: Vec<PathBuf> = self
+                    .visible_files()
+                    .into_iter()
+                    .filter(|(_, f)| {
+                        self.selection.is_empty() || self.selection.contains(&f.absolute_path)
+                    })
+                    .filter(|(_, f)| f.source == FileSource::Disk)
+                    .map(|(_, f)| PathBuf::from(&f.absolute_path))
+                    .collect();
+                if sources.is_empty() {
+                    self.status_message = Some(String::from("There are no files to rename"));
+                    return Task::none();
+                }
+
+                let mut batch_rename = BatchRename {
+                    sources,
+                    pattern: String::from("{name}.{ext}"),
+                    find: String::new(),
+                    replace: String::new(),
+                    preview: Vec::new(),
+                };
+                batch_rename.update_preview();
+                self.batch_rename = Some(batch_rename);
+                Task::none()
+            }
+            Action::CompareWithFolder => Task::perform(
+                pick_folder(self.opened_dir.absolute_path.clone()),
+                Action::CompareFolderPicked,
+            ),
+            Action::CompareFolderPicked(folder) => match folder {
+                Some(folder) => self.compare_with(folder),
+                None => Task::none(),
+            },
+            Action::ComparisonFinished(comparison) => {
+                self.status_message = Some(format!(
+                    "Found {} differences with {}",
+                    comparison.entries.len(),
+                    comparison.right.display()
+                ));
+                self.comparison = Some(comparison);
+                Task::none()
+            }
+            Action::SetCompareContents(compare_contents) => {
+                self.compare_contents = compare_contents;
+                match &self.comparison {
+                    Some(comparison) => {
+                        let right = comparison.right.clone();
+                        self.compare_with(right)
+                    }
+                    None => Task::none(),
+                }
+            }
+            Action::CloseComparison => {
+                self.comparison = None;
+                Task::none()
+            }
+            Action::CloseBatchRename => {
+                self.batch_rename = None;
+                Task::none()
+            }
+            Action::BatchRenamePatternChanged(pattern) => {
+                if let Some(batch_rename) = &mut self.batch_rename {
+                    batch_rename.pattern = pattern;
+                    batch_rename.update_preview();
+                }
+                Task::none()
+            }
+            Action::BatchRenameFindChanged(find) => {
+                if let Some(batch_rename) = &mut self.batch_rename {
+                    batch_rename.find = find;
+                    batch_rename.update_preview();
+                }
+                Task::none()
+            }
+            Action::BatchRenameReplaceChanged(replace) => {
+                if let Some(batch_rename) = &mut self.batch_rename {
+                    batch_rename.replace = replace;
+                    batch_rename.update_preview();
+                }
+                Task::none()
+            }
+            Action::ApplyBatchRename => {
+                let Some(batch_rename) = &mut self.batch_rename else {
+                    return Task::none();
+                };
+
+                match apply_batch_rename(&batch_rename.preview) {
+                    Ok(renames) => {
+                        self.status_message = Some(format!("Renamed {} files", renames.len()));
+                        if !renames.is_empty() {
+                            self.undo_stack.push(UndoableOp::BatchRename { renames });
+                        }
+                        self.batch_rename = None;
+                        self.reload_files(None);
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Batch rename failed: {}", e));
+                        batch_rename.update_preview();
+                    }
+                }
+                Task::none()
+            }
+            Action::CloseFileInfoModal => {
+                self.file_info_modal_open = false;
+                self.file_info_modal_node = None;
+                self.file_info_dir_size = None;
+                self.cancel_dir_size();
+                self.cancel_hashing();
+                Task::none()
+            }
+            Action::SetLargeDirThreshold(threshold) => {
+                self.large_dir_threshold = threshold;
+                Task::none()
+            }
+            Action::SetHexViewWindow(window) => {
+                self.hex_view_window = window;
+                // Start the window at a multiple of its size
+                self.hex_view_offset -= self.hex_view_offset % window;
+                self.reload_hex_view();
+                Task::none()
+            }
+            Action::SetHexViewOffset(offset) => {
+                self.hex_view_offset = offset;
+                self.reload_hex_view();
+                Task::none()
+            }
+            Action::LoadLargeDir => {
+                if let Some(LargeDirPrompt { dir, load }) = self.large_dir_prompt.take()
+                    && dir.absolute_path == self.opened_dir.absolute_path
+                {
+                    self.cancel_dir_load();
+                    self.pending_dir_load = Some(PendingDirLoad {
+                        cancel: None,
+                        entry_limit: None,
+                        ..load
+                    });
+                }
+                Task::none()
+            }
+            Action::CancelLargeDir => {
+                // Go back to the directory shown before, which was read already
+                if let Some(previous) = self
+                    .large_dir_prompt
+                    .take()
+                    .and_then(|prompt| prompt.load.previous)
+                    && let Err(e) = self.open_dir(previous)
+                {
+                    log::error!("Could not go back to the previous directory: {}", e);
+                }
+                Task::none()
+            }
+            Action::SetHashAlgorithm(algorithm) => {
+                self.hash_algorithm = algorithm;
+                Task::none()
+            }
+            Action::ComputeHash(path) => {
+                self.cancel_hashing();
+                self.file_hash = Some(FileHash {
+                    path: path.clone(),
+                    algorithm: self.hash_algorithm,
+                    progress: (0, 0),
+                    digest: None,
+                });
+
+                let (task, handle) = Task::run(
+                    hash_file_with_progress(PathBuf::from(&path), self.hash_algorithm),
+                    move |event| Action::HashProgress(path.clone(), event),
+                )
+                .abortable();
+                self.file_hash_handle = Some(handle);
+                task
+            }
+            Action::HashProgress(path, event) => {
+                // Ignore the progress of a file that is not shown anymore
+                let Some(file_hash) = self.file_hash.as_mut().filter(|h| h.path == path) else {
+                    return Task::none();
+                };
+
+                match event {
+                    HashEvent::Progress { hashed, total } => file_hash.progress = (hashed, total),
+                    HashEvent::Finished(result) => {
+                        file_hash.digest = Some(result);
+                        self.file_hash_handle = None;
+                    }
+                }
+                Task::none()
+            }
+            Action::DirectorySizeComputed(path, size, limited) => {
+                // Ignore results for a modal that was closed or shows another file
+                if let Some(node) = &self.file_info_modal_node
+                    && node.absolute_path == path
+                {
+                    self.file_info_dir_size = Some((size, limited));
+                }
+                Task::none()
+            }
+            // Runs when the user types in the address bar
+            Action::AddressBarChanged(input) => {
+                self.address_bar.focused = true;
+                self.address_bar.input = input;
+                self.address_bar.completions.clear();
+                self.address_bar.completion_index = None;
+                self.address_bar.error = None;
+                Task::none()
+            }
+            // Runs when Tab is pressed
+            Action::CompleteAddress => {
+                if self.address_bar.focused {
+                    self.complete_address();
+                }
+                Task::none()
+            }
+            // Runs when a path is submitted from the address bar
+            Action::NavigateTo(path) => {
+                let navigated = resolve_typed_path(&path, &self.opened_dir.absolute_path)
+                    .and_then(|resolved| self.navigate_to_file(&resolved));
+                match navigated {
+                    Ok(()) => self.address_bar.error = None,
+                    Err(e) => {
+                        log::warn!("Could not navigate to {}: {}", path, e);
+                        self.address_bar.error = Some(format!("Cannot open {}: {}", path, e));
+                    }
+                }
+                Task::none()
+            }
+            Action::OpenSettingsModal => {
+                self.settings_modal_open = true;
+                Task::none()
+            }
+            Action::CloseSettingsModal => {
+                self.settings_modal_open = false;
+                Task::none()
+            }
+            Action::SetRowDensity(density) => {
+                self.row_density = density;
+                Task::none()
+            }
+            Action::SetTreeIndent(indent) => {
+                self.tree_indent = indent;
+                Task::none()
+            }
+            Action::SetColorMode(color_mode) => {
+                self.color_mode = color_mode;
+                // The automatic syntax theme follows the color mode
+                self.highlight_opened_file();
+                Task::none()
+            }
+            Action::SetWrapMode(wrap_mode) => {
+                self.wrap_mode = wrap_mode;
+                Task::none()
+            }
+            Action::SetExtensionDisplay(extension_display) => {
+                self.extension_display = extension_display;
+                Task::none()
+            }
+            Action::SetSortMode(sort_mode) => {
+                self.sort_mode = sort_mode;
+                self.store_dir_view();
+
+                // Keep the same file selected, wherever it moves to
+                let selected = self
+                    .selected_index
+                    .and_then(|index| self.files.get(index))
+                    .map(|f| f.absolute_path.clone());
+                self.collapse_all();
+                sort_nodes(&mut self.files, sort_mode);
+                match selected {
+                    Some(path) => self.select_path(&path),
+                    None => self.page = 0,
+                }
+                Task::none()
+            }
+            Action::SetShowWhitespace(show_whitespace) => {
+                self.show_whitespace = show_whitespace;
+                Task::none()
+            }
+            Action::SetShowLineNumbers(show_line_numbers) => {
+                self.show_line_numbers = show_line_numbers;
+                Task::none()
+            }
+            Action::SetSyntaxTheme(syntax_theme) => {
+                self.syntax_theme = syntax_theme;
+                self.highlight_opened_file();
+                Task::none()
+            }
+            Action::OpenAssociationsModal => {
+                // Only one modal is shown at a time
+                self.settings_modal_open = false;
+                self.associations_modal_open = true;
+                Task::none()
+            }
+            Action::CloseAssociationsModal => {
+                self.associations_modal_open = false;
+                self.new_association_extension.clear();
+                Task::none()
+            }
+            Action::NewAssociationExtensionChanged(extension) => {
+                self.new_association_extension = extension;
+                Task::none()
+            }
+            Action::SetAssociation(extension, behavior) => {
+                self.associations.set(&extension, behavior);
+                if extension == self.new_association_extension {
+                    self.new_association_extension.clear();
+                }
+                Task::none()
+            }
+            Action::RemoveAssociation(extension) => {
+                self.associations.remove(&extension);
+                Task::none()
+            }
+            Action::NextPage => {
+                if self.page + 1 < self.page_count() {
+                    self.page += 1;
+                }
+                Task::none()
+            }
+            Action::PreviousPage => {
+                self.page = self.page.saturating_sub(1);
+                Task::none()
+            }
+            // Runs when the folder picker for "Copy To" closes
+            Action::CopyToFolderPicked(sources, folder) => {
+                // The picker was cancelled
+                let Some(folder) = folder else {
+                    return Task::none();
+                };
+
+                let copies: Vec<(PathBuf, String)> = sources
+                    .into_iter()
+                    .map(|source| {
+                        let source = PathBuf::from(source);
+                        let file_name = source
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or_default()
+                            .to_string();
+                        (source, file_name)
+                    })
+                    .collect();
+
+                self.copy_progress = Some((0, 0));
+                self.status_message = Some(match copies.as_slice() {
+                    [(_, file_name)] => format!("Copying {}...", file_name),
+                    _ => format!("Copying {} files...", copies.len()),
+                });
+                // The files are copied one after the other
+                copies
+                    .into_iter()
+                    .map(|(source, file_name)| {
+                        // Append a suffix rather than overwriting an existing file
+                        let destination = unique_destination(&folder, &file_name);
+                        Task::run(
+                            copy_file_with_progress(source, destination),
+                            Action::CopyProgress,
+                        )
+                    })
+                    .fold(Task::none(), Task::chain)
+            }
+            Action::CopyProgress(event) => {
+                match event {
+                    CopyEvent::Progress { copied, total } => {
+                        self.copy_progress = Some((copied, total));
+                    }
+                    CopyEvent::Finished(Ok(destination)) => {
+                        self.copy_progress = None;
+                        self.undo_stack.push(UndoableOp::Copy {
+                            destination: destination.clone(),
+                        });
+                        self.status_message = Some(format!("Copied to {}", destination.display()));
+                        // Show the copy if it landed in the opened directory
+                        if destination.parent() == Some(Path::new(&self.opened_dir.absolute_path)) {
+                            self.reload_files(None);
+                        }
+                    }
+                    CopyEvent::Finished(Err(e)) => {
+                        self.copy_progress = None;
+                        self.status_message = Some(format!("Copy failed: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            // Runs when the folder picker for "Extract To" closes
+            Action::ExtractToFolderPicked(archive, folder) => match folder {
+                Some(folder) => self.extract_archive(archive, &folder),
+                // The picker was cancelled
+                None => Task::none(),
+            },
+            Action::ExtractProgress(event) => {
+                match event {
+                    ExtractEvent::Progress { extracted, total } => {
+                        self.extract_progress = Some((extracted, total));
+                    }
+                    ExtractEvent::Finished(Ok(destination)) => {
+                        self.extract_progress = None;
+                        self.status_message =
+                            Some(format!("Extracted to {}", destination.display()));
+                        // Show the new folder if it landed in the opened directory
+                        if destination.parent() == Some(Path::new(&self.opened_dir.absolute_path)) {
+                            self.reload_files(None);
+                        }
+                    }
+                    ExtractEvent::Finished(Err(e)) => {
+                        self.extract_progress = None;
+                        self.status_message = Some(format!("Extraction failed: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            Action::SaveAs => {
+                let Some(file) = &self.opened_file else {
+                    return Task::none();
+                };
+                let start_dir = file.parent_folder.clone().unwrap_or_default();
+                Task::perform(
+                    save_file(start_dir, file.file_name.clone()),
+                    Action::SaveAsPathPicked,
+                )
+            }
+            // Runs when the save dialog for "Save As" closes
+            Action::SaveAsPathPicked(destination) => {
+                let (Some(destination), Some(file)) = (destination, &self.opened_file) else {
+                    return Task::none();
+                };
+
+                // Files on disk are copied byte for byte, as the shown text was decoded
+                // from their encoding. Entries of archives can only be saved as text.
+                let contents = match (
+                    &file.source,
+                    &self.opened_file_behavior,
+                    &self.opened_file_contents,
+                ) {
+                    (FileSource::Disk, _, _) => None,
+                    (_, OpenBehavior::InternalText, Ok(contents)) => Some(contents.clone()),
+                    _ => None,
+                };
+                // Only the start of long files is read, which would save a cut off copy
+                if contents.is_some() && self.opened_file_truncated {
+                    self.status_message = Some(format!(
+                        "Only the start of {} was read, so it cannot be saved from the archive",
+                        file.file_name
+                    ));
+                    return Task::none();
+                }
+                if contents.is_none() && file.source != FileSource::Disk {
+                    self.status_message =
+                        Some(String::from("Files within archives cannot be copied yet"));
+                    return Task::none();
+                }
+
+                Task::perform(
+                    save_copy(PathBuf::from(&file.absolute_path), destination, contents),
+                    Action::SaveAsFinished,
+                )
+            }
+            Action::ToggleCommandPalette => {
+                self.command_palette_open = !self.command_palette_open;
+                self.command_palette_query.clear();
+                if self.command_palette_open {
+                    return operation::focus(COMMAND_PALETTE_INPUT_ID);
+                }
+                Task::none()
+            }
+            Action::CloseCommandPalette => {
+                self.command_palette_open = false;
+                self.command_palette_query.clear();
+                Task::none()
+            }
+            Action::CommandPaletteQueryChanged(query) => {
+                self.command_palette_query = query;
+                Task::none()
+            }
+            Action::ToggleExpanded(index) => {
+                let Some(dir) = self.files.get(index).filter(|f| f.is_dir) else {
+                    return Task::none();
+                };
+
+                let selected = self
+                    .selected_index
+                    .and_then(|index| self.files.get(index))
+                    .map(|f| f.absolute_path.clone());
+
+                if dir.expanded {
+                    let depth = dir.depth;
+                    let descendants = self.files[index + 1..]
+                        .iter()
+                        .take_while(|f| f.depth > depth)
+                        .count();
+                    self.files.drain(index + 1..index + 1 + descendants);
+                    self.files[index].expanded = false;
+                } else {
+                    // Listed links keep their own path, so the cycle is checked on the link itself
+                    if dir.is_symlink && is_symlink_cycle(Path::new(&dir.absolute_path)) {
+                        self.status_message = Some(format!(
+                            "{} links to a folder it is within, so it can't be expanded",
+                            dir.file_name
+                        ));
+                        return Task::none();
+                    }
+
+                    let children = match &dir.children {
+                        Some(children) => children.clone(),
+                        None => match list_dir(dir) {
+                            Ok(mut children) => {
+                                for child in &mut children {
+                                    child.depth = dir.depth + 1;
+                                }
+                                sort_nodes(&mut children, self.sort_mode);
+                                children
+                            }
+                            Err(e) => {
+                                self.status_message =
+                                    Some(format!("Could not read {}: {}", dir.file_name, e));
+                                return Task::none();
+                            }
+                        },
+                    };
+                    let dir = &mut self.files[index];
+                    dir.children = Some(children.clone());
+                    dir.expanded = true;
+                    self.files.splice(index + 1..index + 1, children);
+                }
+
+                // Stay on the same page, with the same file selected
+                let page = self.page;
+                self.apply_filters();
+                self.page = page.min(self.page_count() - 1);
+                self.selected_index = selected
+                    .and_then(|path| self.files.iter().position(|f| f.absolute_path == path));
+                Task::none()
+            }
+            Action::Delete(index) => {
+                let files = self.selected_or(index);
+                if files.iter().any(|f| f.source != FileSource::Disk) {
+                    self.status_message =
+                        Some(String::from("Files within archives cannot be deleted"));
+                    return Task::none();
+                }
+                self.pending_delete = files;
+                Task::none()
+            }
+            Action::ConfirmDelete => {
+                let files = std::mem::take(&mut self.pending_delete);
+
+                let mut deleted: Vec<PathBuf> = Vec::new();
+                let mut error = None;
+                for file in &files {
+                    // A link is deleted at the path it was listed at, never at its target
+                    let path = match &file.parent_folder {
+                        Some(parent) if file.is_symlink => Path::new(parent).join(&file.file_name),
+                        _ => PathBuf::from(&file.absolute_path),
+                    };
+                    // Selected files within a selected folder are deleted with it
+                    if deleted.iter().any(|folder| path.starts_with(folder)) {
+                        continue;
+                    }
+                    let still_a_link = fs::symlink_metadata(&path)
+                        .is_ok_and(|metadata| metadata.file_type().is_symlink());
+                    if file.is_symlink && !still_a_link {
+                        error = Some(format!(
+                            "Did not delete {}: it is no longer a symbolic link",
+                            file.file_name
+                        ));
+                        break;
+                    }
+                    match delete_path(&path) {
+                        Ok(()) => deleted.push(path),
+                        Err(e) => {
+                            error = Some(format!("Could not delete {}: {}", file.file_name, e));
+                            break;
+                        }
+                    }
+                }
+                self.status_message = match (error, files.as_slice()) {
+                    (Some(error), _) => Some(error),
+                    (None, [file]) => Some(format!("Deleted {}", file.file_name)),
+                    (None, files) => Some(format!("Deleted {} items", files.len())),
+                };
+                if deleted.is_empty() {
+                    return Task::none();
+                }
+                self.dir_scroll_offsets
+                    .retain(|dir, _| !deleted.iter().any(|path| Path::new(dir).starts_with(path)));
+                self.selection.clear();
+
+                // The viewer may show a deleted file, or a file within a deleted folder
+                let viewer_shows_deleted = self.opened_file.as_ref().is_some_and(|opened| {
+                    deleted
+                        .iter()
+                        .any(|path| Path::new(&opened.absolute_path).starts_with(path))
+                });
+                self.reload_files(None);
+                if viewer_shows_deleted {
+                    return self.post_update(Action::CloseFile);
+                }
+                Task::none()
+            }
+            Action::CancelDelete => {
+                self.pending_delete.clear();
+                Task::none()
+            }
+            Action::StartRename(index) => {
+                let Some(file) = self.files.get(index) else {
+                    return Task::none();
+                };
+                if file.source != FileSource::Disk {
+                    self.status_message =
+                        Some(String::from("Files within archives cannot be renamed"));
+                    return Task::none();
+                }
+                self.renaming = Some((file.absolute_path.clone(), file.file_name.clone()));
+                operation::focus(RENAME_INPUT_ID)
+            }
+            Action::RenameSelected => match self.selected_index {
+                Some(index) => self.post_update(Action::StartRename(index)),
+                None => Task::none(),
+            },
+            Action::OpenExternal(index) => {
+                if let Some(file) = self.files.get(index).cloned() {
+                    self.launch_externally(&file);
+                }
+                Task::none()
+            }
+            Action::CopyFile(index) => {
+                let files = self.selected_or(index);
+                if files.is_empty() {
+                    return Task::none();
+                }
+                if files
+                    .iter()
+                    .any(|f| f.is_dir || f.source != FileSource::Disk)
+                {
+                    self.status_message =
+                        Some(String::from("Only files outside archives can be copied"));
+                    return Task::none();
+                }
+
+                self.status_message = match files.as_slice() {
+                    [file] => Some(format!("Copied {}", file.file_name)),
+                    _ => Some(format!("Copied {} files", files.len())),
+                };
+                let paths = files.iter().map(|f| PathBuf::from(&f.absolute_path));
+                self.clipboard = Some((paths.collect(), ClipboardOp::Copy));
+                Task::none()
+            }
+            Action::CutFile(index) => {
+                let files = self.selected_or(index);
+                if files.is_empty() {
+                    return Task::none();
+                }
+                if files.iter().any(|f| f.source != FileSource::Disk) {
+                    self.status_message =
+                        Some(String::from("Files within archives cannot be moved"));
+                    return Task::none();
+                }
+
+                self.status_message = match files.as_slice() {
+                    [file] => Some(format!("Cut {}", file.file_name)),
+                    _ => Some(format!("Cut {} items", files.len())),
+                };
+                let paths = files.iter().map(|f| PathBuf::from(&f.absolute_path));
+                self.clipboard = Some((paths.collect(), ClipboardOp::Cut));
+                Task::none()
+            }
+            Action::Paste => {
+                let Some((sources, op)) = self.clipboard.clone() else {
+                    return Task::none();
+                };
+                if self.opened_dir.source != FileSource::Disk {
+                    self.status_message =
+                        Some(String::from("Files cannot be pasted into archives"));
+                    return Task::none();
+                }
+
+                let folder = PathBuf::from(&self.opened_dir.absolute_path);
+                match op {
+                    // Copies show their progress like "Copy To"
+                    ClipboardOp::Copy => self.post_update(Action::CopyToFolderPicked(
+                        sources
+                            .iter()
+                            .map(|source| source.to_string_lossy().into_owned())
+                            .collect(),
+                        Some(folder),
+                    )),
+                    ClipboardOp::Cut => {
+                        if sources
+                            .iter()
+                            .any(|source| source.parent() == Some(folder.as_path()))
+                        {
+                            self.status_message =
+                                Some(String::from("The files are already in this folder"));
+                            return Task::none();
+                        }
+
+                        let mut moves: Vec<(PathBuf, PathBuf)> = Vec::new();
+                        let mut error = None;
+                        for source in sources {
+                            // Files within a moved folder are moved with it
+                            if moves.iter().any(|(moved, _)| source.starts_with(moved)) {
+                                continue;
+                            }
+                            match move_path(&source, &folder) {
+                                Ok(destination) => {
+                                    self.follow_rename(&source, &destination);
+                                    moves.push((source, destination));
+                                }
+                                Err(e) => {
+                                    error =
+                                        Some(format!("Could not move {}: {}", source.display(), e));
+                                    break;
+                                }
+                            }
+                        }
+                        self.status_message = match (error, moves.as_slice()) {
+                            (Some(error), _) => Some(error),
+                            (None, [(source, destination)]) => Some(format!(
+                                "Moved {} to {}",
+                                source.display(),
+                                destination.display()
+                            )),
+                            (None, moves) => Some(format!(
+                                "Moved {} items to {}",
+                                moves.len(),
+                                folder.display()
+                            )),
+                        };
+                        let Some((_, last)) = moves.last().cloned() else {
+                            return Task::none();
+                        };
+
+                        // Cut files can only be pasted once
+                        self.clipboard = None;
+                        self.undo_stack.push(UndoableOp::Move { moves });
+                        self.reload_files(Some(last.to_string_lossy().into_owned()));
+                        Task::none()
+                    }
+                }
+            }
+            Action::OpenViewedFileExternally => {
+                if let Some(file) = self.opened_file.clone() {
+                    self.launch_externally(&file);
+                }
+                Task::none()
+            }
+            Action::SetExternalEditor(command) => {
+                self.external_editor = command;
+                Task::none()
+            }
+            Action::Refresh => {
+                if self.showing_search_results {
+                    return self.search_recursively();
+                }
+
+                // Keep the same file selected, if it still exists
+                let selected = self
+                    .selected_index
+                    .and_then(|index| self.files.get(index))
+                    .map(|f| f.absolute_path.clone());
+                self.reload_files(selected);
+                Task::none()
+            }
+            Action::SetRenameName(name) => {
+                if let Some((_, new_name)) = &mut self.renaming {
+                    *new_name = name;
+                }
+                Task::none()
+            }
+            Action::CancelRename => {
+                self.renaming = None;
+                Task::none()
+            }
+            Action::Rename(index, new_name) => {
+                let Some(file) = self.files.get(index) else {
+                    return Task::none();
+                };
+                if new_name == file.file_name {
+                    self.renaming = None;
+                    return Task::none();
+                }
+
+                let source = PathBuf::from(&file.absolute_path);
+                let destination = match rename_path(&source, &new_name) {
+                    Ok(destination) => destination,
+                    // Keep the field open so the name can be corrected
+                    Err(e) => {
+                        self.status_message =
+                            Some(format!("Could not rename {}: {}", file.file_name, e));
+                        return Task::none();
+                    }
+                };
+
+                self.status_message = Some(format!("Renamed {} to {}", file.file_name, new_name));
+                self.renaming = None;
+                self.follow_rename(&source, &destination);
+                self.undo_stack.push(UndoableOp::Rename {
+                    source,
+                    destination: destination.clone(),
+                });
+                self.reload_files(Some(destination.to_string_lossy().into_owned()));
+                Task::none()
+            }
+            Action::OpenNewFolder => {
+                if self.opened_dir.source != FileSource::Disk {
+                    self.status_message =
+                        Some(String::from("Folders cannot be created within archives"));
+                    return Task::none();
+                }
+                self.new_folder_name = Some(String::new());
+                operation::focus(NEW_FOLDER_INPUT_ID)
+            }
+            Action::SetNewFolderName(name) => {
+                self.new_folder_name = Some(name);
+                Task::none()
+            }
+            Action::CancelNewFolder => {
+                self.new_folder_name = None;
+                Task::none()
+            }
+            Action::CreateFolder(name) => {
+                let dir = PathBuf::from(&self.opened_dir.absolute_path);
+                match create_folder(&dir, name.trim()) {
+                    Ok(path) => {
+                        self.status_message = Some(format!("Created {}", path.display()));
+                        self.undo_stack
+                            .push(UndoableOp::CreateFolder { path: path.clone() });
+                        self.new_folder_name = None;
+                        self.reload_files(Some(path.to_string_lossy().into_owned()));
+                    }
+                    // Keep the field open so the name can be corrected
+                    Err(e) => {
+                        self.status_message = Some(format!("Could not create {}: {}", name, e));
+                    }
+                }
+                Task::none()
+            }
+            Action::Undo => {
+                let Some(op) = self.undo_stack.last() else {
+                    self.status_message = Some(String::from("Nothing to undo"));
+                    return Task::none();
+                };
+
+                // A failed undo stays on the stack, so it can be retried
+                self.status_message = Some(match op.undo() {
+                    Ok(description) => {
+                        self.undo_stack.pop();
+                        description
+                    }
+                    Err(e) => format!("Undo failed: {}", e),
+                });
+                self.reload_files(None);
+                Task::none()
+            }
+            Action::ToggleSidebar => {
+                self.sidebar_visible = !self.sidebar_visible;
+                Task::none()
+            }
+            Action::OpenInNewTab(index) => {
+                let Some(dir) = self.files.get(index).filter(|f| f.is_dir).cloned() else {
+                    return Task::none();
+                };
+
+                self.save_active_tab();
+                self.tabs.push(Tab::new(dir.clone()));
+                self.active_tab = self.tabs.len() - 1;
+                // The file of the previous tab stays with it
+                let _ = self.post_update(Action::CloseFile);
+                if let Err(e) = self.open_file(dir) {
+                    log::error!("Could not open the new tab: {}", e);
+                }
+                Task::none()
+            }
+            Action::NewTab => {
+                // The new tab starts as a copy of the current one, so its directory
+                // (already listed, or still being read) isn't opened again
+                self.save_active_tab();
+                let tab = self.tabs[self.active_tab].clone();
+                self.tabs.push(tab);
+                self.restore_tab(self.tabs.len() - 1);
+                Task::none()
+            }
+            Action::CloseActiveTab => self.post_update(Action::CloseTab(self.active_tab)),
+            Action::SwitchTab(index) => {
+                if index != self.active_tab && index < self.tabs.len() {
+                    self.save_active_tab();
+                    self.restore_tab(index);
+                }
+                Task::none()
+            }
+            Action::CloseTab(index) => {
+                // The last tab stays open
+                if self.tabs.len() < 2 || index >= self.tabs.len() {
+                    return Task::none();
+                }
+
+                self.tabs.remove(index);
+                if index == self.active_tab {
+                    self.restore_tab(index.min(self.tabs.len() - 1));
+                } else if index < self.active_tab {
+                    self.active_tab -= 1;
+                }
+                Task::none()
+            }
+            Action::SetOpenDirsInNewTab(open_dirs_in_new_tab) => {
+                self.open_dirs_in_new_tab = open_dirs_in_new_tab;
+                Task::none()
+            }
+            Action::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                Task::none()
+            }
+            Action::ToggleQuickAccess => {
+                self.quick_access_open = !self.quick_access_open;
+                // Volumes may have been mounted or removed since the last time
+                if self.quick_access_open {
+                    self.places = quick_access_places();
+                }
+                Task::none()
+            }
+            Action::AddBookmark(path) => {
+                if !self.bookmarks.contains(&path) {
+                    self.bookmarks.push(path);
+                }
+                Task::none()
+            }
+            Action::RemoveBookmark(path) => {
+                self.bookmarks.retain(|bookmark| *bookmark != path);
+                Task::none()
+            }
+            Action::FocusNext => operation::focus_next(),
+            Action::FocusPrevious => operation::focus_previous(),
+            Action::HoverFile(index) => {
+                let Some(file) = self.files.get(index) else {
+                    return Task::none();
+                };
+                if file.is_dir
+                    || file.source != FileSource::Disk
+                    || self.previews.contains_key(&file.absolute_path)
+                {
+                    return Task::none();
+                }
+
+                // Cache the path right away so hovering again doesn't read it twice
+                let path = file.absolute_path.clone();
+                self.previews.insert(path.clone(), None);
+                Task::perform(
+                    tokio::task::spawn_blocking(move || {
+                        let preview = text_preview(&path, PREVIEW_MAX_LINES);
+                        (path, preview)
+                    }),
+                    |result| match result {
+                        Ok((path, preview)) => Action::PreviewLoaded(path, preview),
+                        Err(e) => {
+                            log::error!("Could not read the preview: {}", e);
+                            Action::PreviewLoaded(String::new(), None)
+                        }
+                    },
+                )
+            }
+            Action::PreviewLoaded(path, preview) => {
+                if let Some(cached) = self.previews.get_mut(&path) {
+                    *cached = preview;
+                }
+                Task::none()
+            }
+            Action::SetRelativeTimes(relative_times) => {
+                self.relative_times = relative_times;
+                Task::none()
+            }
+            // Nothing changes, but the view is drawn again with the current time
+            Action::RefreshRelativeTimes => Task::none(),
+            Action::SetShowThumbnails(show_thumbnails) => {
+                self.show_thumbnails = show_thumbnails;
+                Task::none()
+            }
+            Action::ThumbnailLoaded(key, thumbnail) => {
+                // Skip thumbnails that were dropped from the cache in the meantime
+                if self.thumbnails.contains(&key) {
+                    self.thumbnails.insert(key, thumbnail);
+                }
+                Task::none()
+            }
+            Action::SetShowChildCounts(show_child_counts) => {
+                self.show_child_counts = show_child_counts;
+                Task::none()
+            }
+            Action::ChildCountLoaded(path, count) => {
+                // Skip counts that were dropped from the cache in the meantime
+                if self.child_counts.contains_key(&path) {
+                    self.child_counts.insert(path, count);
+                }
+                Task::none()
+            }
+            Action::FileTreeScrolled(offset) => {
+                self.file_tree_offset = offset;
+                Task::none()
+            }
+            Action::DirLoaded(path, result) => {
+                // Ignore the listings of directories that were navigated away from
+                if !self
+                    .pending_dir_load
+                    .as_ref()
+                    .is_some_and(|load| load.path == path)
+                {
+                    return Task::none();
+                }
+                let select = self.pending_dir_load.take().and_then(|load| load.select);
+
+                match result {
+                    Ok(Cancelable::Completed(files)) => self.show_listing(files, select),
+                    Ok(Cancelable::Cancelled) => {}
+                    Err((kind, e)) => {
+                        self.status_message = Some(format!("Could not read {}: {}", path, e));
+                        // The directory may have been removed, or become unreadable
+                        self.files.clear();
+                        self.apply_filters();
+                        self.dir_error = Some((kind, e));
+                    }
+                }
+                Task::none()
+            }
+            Action::LargeDirFound(path) => {
+                if let Some(load) = self
+                    .pending_dir_load
+                    .take_if(|load| load.path == path && load.entry_limit.is_some())
+                {
+                    self.large_dir_prompt = Some(LargeDirPrompt {
+                        dir: self.opened_dir.clone(),
+                        load,
+                    });
+                }
+                Task::none()
+            }
+            Action::ToggleLogPanel => {
+                self.log_panel_open = !self.log_panel_open;
+                Task::none()
+            }
+            Action::ClearLog => {
+                logging::clear();
+                Task::none()
+            }
+            // Runs when Enter is pressed in the command palette
+            Action::RunFirstCommand => {
+                match filter_commands(self, &self.command_palette_query)
+                    .into_iter()
+                    .next()
+                {
+                    Some(command) => self.post_update(Action::RunCommand(Box::new(command.action))),
+                    None => Task::none(),
+                }
+            }
+            Action::RunCommand(action) => {
+                self.command_palette_open = false;
+                self.command_palette_query.clear();
+                self.post_update(*action)
+            }
+            // Runs when an encoding is picked in the status bar
+            Action::SetEncoding(encoding) => {
+                let Some(file) = &self.opened_file else {
+                    return Task::none();
+                };
+                let file = file.clone();
+                self.opened_file_contents = self.read_text(&file, Some(encoding));
+                self.opened_file_stats = self.compute_file_stats();
+                self.highlighted = None;
+                self.highlight_opened_file();
+                self.update_find_matches();
+                Task::none()
+            }
+            Action::LoadMoreText => {
+                let Some(file) = &self.opened_file else {
+                    return Task::none();
+                };
+                let file = file.clone();
+                self.opened_file_read_limit += TEXT_VIEW_CHUNK_BYTES;
+                self.opened_file_contents = self.read_text(&file, self.opened_file_encoding);
+                self.opened_file_stats = self.compute_file_stats();
+                self.highlighted = None;
+                self.highlight_opened_file();
+                self.update_find_matches();
+                Task::none()
+            }
+            Action::FindQueryChanged(query) => {
+                self.find_query = query;
+                self.update_find_matches();
+                Task::none()
+            }
+            Action::JumpToLine(line) => {
+                let line_count = match &self.opened_file_contents {
+                    Ok(contents) => contents.lines().count(),
+                    Err(_) => return Task::none(),
+                };
+                let y = line as f32 / line_count.saturating_sub(1).max(1) as f32;
+                operation::snap_to(
+                    FILE_CONTENTS_SCROLLABLE_ID,
+                    scrollable::RelativeOffset { x: 0.0, y },
+                )
+            }
+            Action::SelectNext | Action::SelectPrevious => {
+                let visible: Vec<usize> =
+                    self.visible_files().into_iter().map(|(i, _)| i).collect();
+                if visible.is_empty() {
+                    return Task::none();
+                }
+
+                let position = self
+                    .selected_index
+                    .and_then(|selected| visible.iter().position(|&i| i == selected));
+                let position = match (position, action) {
+                    (None, Action::SelectNext) => 0,
+                    (None, _) => visible.len() - 1,
+                    (Some(position), Action::SelectNext) => (position + 1).min(visible.len() - 1),
+                    (Some(position), _) => position.saturating_sub(1),
+                };
+                self.selection.clear();
+                self.selected_index = Some(visible[position]);
+                self.scroll_to_selected()
+            }
+            Action::TypeAhead(text) => {
+                let now = Instant::now();
+                if self
+                    .type_ahead_at
+                    .is_none_or(|at| now.duration_since(at) > TYPE_AHEAD_TIMEOUT)
+                {
+                    self.type_ahead.clear();
+                }
+                self.type_ahead_at = Some(now);
+                self.type_ahead.push_str(&text.to_lowercase());
+
+                let visible: Vec<(usize, String)> = self
+                    .visible_files()
+                    .into_iter()
+                    .map(|(i, file)| (i, file.file_name.to_lowercase()))
+                    .collect();
+                let position = self
+                    .selected_index
+                    .and_then(|selected| visible.iter().position(|(i, _)| *i == selected));
+
+                // Typing the same letter again cycles through the names starting with it
+                let first = self.type_ahead.chars().next();
+                let repeated = self.type_ahead.chars().all(|c| Some(c) == first);
+                let prefix = match (repeated, first) {
+                    (true, Some(first)) => first.to_string(),
+                    _ => self.type_ahead.clone(),
+                };
+                let start = match position {
+                    Some(position) if repeated => position + 1,
+                    Some(position) => position,
+                    None => 0,
+                };
+                let found = (0..visible.len())
+                    .map(|offset| (start + offset) % visible.len())
+                    .find(|&position| visible[position].1.starts_with(&prefix));
+
+                match found {
+                    Some(position) => {
+                        self.selection.clear();
+                        self.selected_index = Some(visible[position].0);
+                        self.scroll_to_selected()
+                    }
+                    None => Task::none(),
+                }
+            }
+            Action::OpenSelected => match self.selected_index {
+                Some(index) => self.post_update(Action::OpenFile(index)),
+                None => Task::none(),
+            },
+            Action::ToggleFileDetails => {
+                self.show_file_details = !self.show_file_details;
+                Task::none()
+            }
+            Action::FocusFind => match self.opened_file {
+                Some(_) => operation::focus(FIND_INPUT_ID),
+                None => Task::none(),
+            },
+            Action::FindNext | Action::FindPrevious => {
+                let count = self.find_matches.len();
+                if count == 0 {
+                    return Task::none();
+                }
+
+                // Wrap around at either end of the file
+                let current = match (self.find_current, action) {
+                    (None, Action::FindNext) => 0,
+                    (None, _) => count - 1,
+                    (Some(current), Action::FindNext) => (current + 1) % count,
+                    (Some(current), _) => (current + count - 1) % count,
+                };
+                self.find_current = Some(current);
+                self.post_update(Action::JumpToLine(self.find_matches[current]))
+            }
+            Action::SetRenderMode(behavior) => {
+                let Some(file) = self.opened_file.clone() else {
+                    return Task::none();
+                };
+                self.load_opened_file(&file, behavior);
+                self.remember_opened_view();
+                Task::none()
+            }
+            Action::SetWrap(wrap) => {
+                self.opened_file_view.wrap = wrap;
+                self.remember_opened_view();
+                Task::none()
+            }
+            Action::SetViewFontSize(font_size) => {
+                self.opened_file_view.font_size =
+                    font_size.clamp(MIN_VIEW_FONT_SIZE, MAX_VIEW_FONT_SIZE);
+                self.remember_opened_view();
+                Task::none()
+            }
+            Action::RememberViewForType(remember) => {
+                let Some(extension) = self.opened_file_extension() else {
+                    return Task::none();
+                };
+                if remember {
+                    self.associations.remember_view(
+                        &extension,
+                        self.opened_file_behavior,
+                        self.opened_file_view,
+                    );
+                } else {
+                    self.associations.forget_view(&extension);
+                }
+                Task::none()
+            }
+            Action::SaveAsFinished(result) => {
+                match result {
+                    Ok(destination) => {
+                        self.status_message = Some(format!("Saved to {}", destination.display()));
+                        if destination.parent() == Some(Path::new(&self.opened_dir.absolute_path)) {
+                            self.reload_files(None);
+                        }
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Save failed: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            #[cfg(feature = "serde")]
+            Action::ExportListing(ExportTarget::Clipboard, recursive) => {
+                let Some(job) = self.listing_json_job(recursive) else {
+                    return Task::none();
+                };
+                Task::perform(tokio::task::spawn_blocking(job), |result| match result {
+                    Ok(json) => Action::ListingJsonReady(json),
+                    Err(e) => Action::ListingJsonReady(Err(e.to_string())),
+                })
+            }
+            #[cfg(feature = "serde")]
+            Action::ExportListing(ExportTarget::File, recursive) => Task::perform(
+                save_file(
+                    self.opened_dir.absolute_path.clone(),
+                    format!("{}.json", self.opened_dir.file_name),
+                ),
+                move |destination| Action::ExportListingPathPicked(destination, recursive),
+            ),
+            #[cfg(feature = "serde")]
+            Action::ExportListingPathPicked(destination, recursive) => {
+                let (Some(destination), Some(job)) =
+                    (destination, self.listing_json_job(recursive))
+                else {
+                    return Task::none();
+                };
+                Task::perform(
+                    tokio::task::spawn_blocking(move || {
+                        let json = job()?;
+                        fs::write(&destination, json).map_err(|e| e.to_string())?;
+                        Ok(destination)
+                    }),
+                    |result| match result {
+                        Ok(exported) => Action::ListingExported(exported),
+                        Err(e) => Action::ListingExported(Err(e.to_string())),
+                    },
+                )
+            }
+            #[cfg(feature = "serde")]
+            Action::ListingJsonReady(json) => match json {
+                Ok(json) => {
+                    self.status_message = Some(String::from("Copied the listing as JSON"));
+                    iced::clipboard::write(json)
+                }
+                Err(e) => {
+                    self.status_message = Some(format!("Export failed: {}", e));
+                    Task::none()
+                }
+            },
+            #[cfg(feature = "serde")]
+            Action::ListingExported(result) => {
+                self.status_message = Some(match &result {
+                    Ok(destination) => format!("Exported the listing to {}", destination.display()),
+                    Err(e) => format!("Export failed: {}", e),
+                });
+                if let Ok(destination) = result
+                    && destination.parent() == Some(Path::new(&self.opened_dir.absolute_path))
+                {
+                    self.reload_files(None);
+                }
+                Task::none()
+            }
+        }
+    }
+
+    /// Opens a file with the external editor without waiting for it, reporting
+    /// the outcome in the status bar
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `file` - The file to open
+    fn launch_externally(&mut self, file: &FileNode) {
+        if file.source != FileSource::Disk {
+            self.status_message = Some(String::from(
+                "Files within archives cannot be opened externally",
+            ));
+            return;
+        }
+
+        self.status_message = Some(
+            match open_with(&self.external_editor, &file.absolute_path) {
+                Ok(_) => format!("Opened {} externally", file.file_name),
+                Err(e) => format!("Could not open {} externally: {}", file.file_name, e),
+            },
+        );
+    }
+
+    /// Copies the text to the system clipboard and reports it in the status bar
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `value` - The text to copy
+    fn copy_to_clipboard(&mut self, value: String) -> Task<Action> {
+        self.status_message = Some(format!("Copied \"{}\"", value));
+        iced::clipboard::write(value)
+    }
+
+    /// Applies the `filters` to the `files`, setting `matches_filters` on each
+    /// node and returning to the first page.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn apply_filters(&mut self) {
+        let query = self.name_query();
+        let matcher = fuzzy_matcher(query.case_sensitive);
+
+        for file in &mut self.files {
+            file.match_score = None;
+            file.match_indices.clear();
+
+            let matches_kind = match self.filters.kind {
+                KindFilter::All => true,
+                KindFilter::FoldersOnly => file.is_dir,
+                KindFilter::FilesOnly => !file.is_dir,
+            };
+
+            let matches_name = if query.search.is_empty() {
+                true
+            } else {
+                match self.filters.search_mode {
+                    SearchMode::Substring => match query.matched_indices(&file.file_name) {
+                        Some(indices) => {
+                            file.match_indices = indices;
+                            true
+                        }
+                        None => false,
+                    },
+                    SearchMode::Regex => match &query.regex {
+                        // Only the first match is highlighted
+                        Some(regex) => match regex.find(&file.file_name) {
+                            Some(found) => {
+                                file.match_indices = char_indices(&file.file_name, found.range());
+                                true
+                            }
+                            None => false,
+                        },
+                        // An invalid pattern leaves the list unfiltered
+                        None => true,
+                    },
+                    SearchMode::Fuzzy => {
+                        match matcher.fuzzy_indices(&file.file_name, &query.search) {
+                            Some((score, indices)) if score >= MIN_FUZZY_SCORE => {
+                                file.match_score = Some(score);
+                                file.match_indices = indices;
+                                true
+                            }
+                            _ => false,
+                        }
+                    }
+                }
+            };
+
+            let matches_hidden = self.filters.show_hidden || !file.is_hidden();
+
+            let matches_contents = self
+                .filters
+                .content_matches
+                .as_ref()
+                .is_none_or(|matches| matches.contains(&file.absolute_path));
+
+            file.matches_filters =
+                matches_kind && matches_hidden && matches_name && matches_contents;
+        }
+        self.page = 0;
+    }
+
+    /// Returns the file name search along with how it is matched
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn name_query(&mut self) -> NameQuery {
+        let regex = match self.filters.search_mode {
+            SearchMode::Regex => self.compile_name_regex(),
+            SearchMode::Substring | SearchMode::Fuzzy => None,
+        };
+
+        let search = self.filters.file_name_search.trim();
+        NameQuery {
+            search: if self.filters.case_sensitive {
+                String::from(search)
+            } else {
+                search.to_lowercase()
+            },
+            case_sensitive: self.filters.case_sensitive,
+            whole_word: self.filters.whole_word,
+            regex,
+        }
+    }
+
+    /// Compiles the file name search to a regex, unless it was already compiled
+    /// from the same search. Returns `None` if the search is not a valid regex.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn compile_name_regex(&mut self) -> Option<Regex> {
+        let pattern = self.filters.file_name_search.trim();
+        let case_sensitive = self.filters.case_sensitive;
+
+        let compiled = self.filters.name_regex.as_ref().is_some_and(|regex| {
+            regex.pattern == pattern && regex.case_sensitive == case_sensitive
+        });
+        if !compiled {
+            self.filters.name_regex = Some(NameRegex {
+                pattern: String::from(pattern),
+                case_sensitive,
+                compiled: RegexBuilder::new(pattern)
+                    .case_insensitive(!case_sensitive)
+                    .build()
+                    .map_err(|e| e.to_string()),
+            });
+        }
+
+        self.filters
+            .name_regex
+            .as_ref()
+            .and_then(|regex| regex.compiled.as_ref().ok().cloned())
+    }
+
+    /// Reads the `opened_dir` again to pick up changes on disk and re-applies
+    /// the active filters. Directories on disk are read in the background, so
+    /// the files shown are only replaced once the `pending_dir_load` finishes.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `select` - The absolute path of the file node to select once read
+    pub fn reload_files(&mut self, select: Option<String>) {
+        // Stay on the same page where possible, unless a file is selected
+        if select.is_none() {
+            self.pending_scroll = Some((self.page, self.file_tree_offset));
+        }
+
+        // Directories on disk may be slow to read (such as on network drives),
+        // so the files shown are replaced once read in the background
+        if self.opened_dir.source == FileSource::Disk {
+            self.cancel_dir_load();
+            self.pending_dir_load = Some(PendingDirLoad {
+                path: self.opened_dir.absolute_path.clone(),
+                cancel: None,
+                select,
+                entry_limit: None,
+                previous: None,
+            });
+            return;
+        }
+
+        match list_dir(&self.opened_dir) {
+            Ok(files) => self.show_listing(files, select),
+            Err(e) => {
+                log::error!("Could not reload directory: {}", e);
+                self.files.clear();
+                self.apply_filters();
+                self.dir_error = Some((e.kind(), e.to_string()));
+            }
+        }
+    }
+
+    /// Shows the files read from the `opened_dir`, replacing the previous listing
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `files` - The files read from the directory
+    /// * `select` - The absolute path of the file node to select
+    fn show_listing(&mut self, files: Vec<FileNode>, select: Option<String>) {
+        self.files = files;
+        sort_nodes(&mut self.files, self.sort_mode);
+        // The files may have changed on disk since they were last listed
+        self.previews.clear();
+        self.child_counts.clear();
+        self.showing_search_results = false;
+        self.search_results_truncated = false;
+        self.selected_index = None;
+        self.dir_error = None;
+        self.apply_filters();
+        if let Some(path) = select {
+            self.select_path(&path);
+        }
+    }
+
+    /// Returns the files matching the filters, along with their index in `files`
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn visible_files(&self) -> Vec<(usize, &FileNode)> {
+        let mut visible: Vec<(usize, &FileNode)> = self
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.matches_filters)
+            .collect();
+
+        // Show the best fuzzy matches first
+        if self.filters.search_mode == SearchMode::Fuzzy {
+            visible.sort_by_key(|(_, f)| std::cmp::Reverse(f.match_score));
+        }
+
+        visible
+    }
+
+    /// Returns the files shown on the current page
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn paged_files(&self) -> Vec<(usize, &FileNode)> {
+        self.visible_files()
+            .into_iter()
+            .skip(self.page * self.page_size)
+            .take(self.page_size)
+            .collect()
+    }
+
+    /// Generates the missing thumbnails of the images on the current page, in the
+    /// background. Images on other pages are left alone to avoid decoding a whole
+    /// folder at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn load_visible_thumbnails(&mut self) -> Task<Action> {
+        if !self.show_thumbnails {
+            return Task::none();
+        }
+
+        let keys: Vec<ThumbnailKey> = self
+            .paged_files()
+            .into_iter()
+            .filter(|(_, f)| !f.is_dir && f.source == FileSource::Disk)
+            .filter(|(_, f)| {
+                let file_type = determine_file_type(&f.absolute_path);
+                self.associations.behavior_for(file_type.as_deref()) == OpenBehavior::InternalImage
+            })
+            .map(|(_, f)| ThumbnailKey {
+                path: f.absolute_path.clone(),
+                modified_at: f.modified_at.clone(),
+            })
+            .filter(|key| !self.thumbnails.contains(key))
+            .collect();
+
+        let tasks = keys.into_iter().map(|key| {
+            // Cache the key right away so the thumbnail isn't generated twice
+            self.thumbnails.insert(key.clone(), None);
+            Task::perform(
+                tokio::task::spawn_blocking(move || {
+                    let thumbnail = generate_thumbnail(&key.path);
+                    (key, thumbnail)
+                }),
+                |result| match result {
+                    Ok((key, thumbnail)) => Action::ThumbnailLoaded(key, thumbnail),
+                    Err(e) => {
+                        log::error!("Could not generate the thumbnail: {}", e);
+                        Action::ThumbnailLoaded(ThumbnailKey::default(), None)
+                    }
+                },
+            )
+        });
+
+        Task::batch(tasks.collect::<Vec<_>>())
+    }
+
+    /// Counts the items in the folders on the current page of the file tree that
+    /// were not counted yet, in the background
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn load_visible_child_counts(&mut self) -> Task<Action> {
+        if !self.show_child_counts {
+            return Task::none();
+        }
+
+        let paths: Vec<String> = self
+            .paged_files()
+            .into_iter()
+            .filter(|(_, f)| f.is_dir && f.source == FileSource::Disk)
+            .map(|(_, f)| f.absolute_path.clone())
+            .filter(|path| !self.child_counts.contains_key(path))
+            .collect();
+
+        let tasks = paths.into_iter().map(|path| {
+            // Cache the path right away so the folder isn't counted twice
+            self.child_counts.insert(path.clone(), None);
+            let counted_path = path.clone();
+            Task::perform(
+                tokio::task::spawn_blocking(move || count_children(Path::new(&path))),
+                move |result| match result {
+                    Ok(count) => Action::ChildCountLoaded(counted_path, count),
+                    Err(e) => {
+                        log::error!("Could not count the items of the folder: {}", e);
+                        Action::ChildCountLoaded(counted_path, None)
+                    }
+                },
+            )
+        });
+
+        Task::batch(tasks.collect::<Vec<_>>())
+    }
+
+    /// Returns the selected file nodes if the node at the index is one of them,
+    /// or only the node at the index otherwise
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `index` - The index in `files` of the node an action was taken on
+    fn selected_or(&self, index: usize) -> Vec<FileNode> {
+        let Some(file) = self.files.get(index) else {
+            return Vec::new();
+        };
+        if !self.selection.contains(&file.absolute_path) {
+            return vec![file.clone()];
+        }
+
+        self.files
+            .iter()
+            .filter(|f| self.selection.contains(&f.absolute_path))
+            .cloned()
+            .collect()
+    }
+
+    /// Restores the page and scroll offset of the opened directory, once it is listed
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn restore_scroll(&mut self) -> Task<Action> {
+        if self.pending_dir_load.is_some() {
+            return Task::none();
+        }
+        let Some((page, offset)) = self.pending_scroll.take() else {
+            return Task::none();
+        };
+
+        self.page = page.min(self.page_count() - 1);
+        self.file_tree_offset = offset;
+        operation::scroll_to(
+            FILE_TREE_SCROLLABLE_ID,
+            scrollable::AbsoluteOffset { x: 0.0, y: offset },
+        )
+    }
+
+    /// Starts reading the directory of the `pending_dir_load` off the UI thread,
+    /// unless it was already started
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn load_pending_dir(&mut self) -> Task<Action> {
+        let Some(load) = self
+            .pending_dir_load
+            .as_mut()
+            .filter(|load| load.cancel.is_none())
+        else {
+            return Task::none();
+        };
+
+        let cancel = CancelToken::default();
+        load.cancel = Some(cancel.clone());
+        let path = load.path.clone();
+        let loaded_path = path.clone();
+        let entry_limit = load.entry_limit;
+
+        Task::perform(
+            tokio::task::spawn_blocking(move || {
+                // Ask before listing directories large enough to freeze the app
+                if let Some(limit) = entry_limit
+                    && has_more_entries_than(&path, limit)
+                {
+                    return Ok(None);
+                }
+                read_dir_cancelable(&path, &cancel)
+                    .map(Some)
+                    .map_err(|e| (e.kind(), e.to_string()))
+            }),
+            move |result| match result {
+                Ok(Ok(None)) => Action::LargeDirFound(loaded_path),
+                Ok(Ok(Some(listing))) => Action::DirLoaded(loaded_path, Ok(listing)),
+                Ok(Err(e)) => Action::DirLoaded(loaded_path, Err(e)),
+                Err(e) => {
+                    log::error!("Could not read the directory: {}", e);
+                    Action::DirLoaded(loaded_path, Err((std::io::ErrorKind::Other, e.to_string())))
+                }
+            },
+        )
+    }
+
+    /// Returns the number of pages of files matching the filters (at least 1)
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn page_count(&self) -> usize {
+        self.visible_files().len().div_ceil(self.page_size).max(1)
+    }
+
+    /// Completes the address bar input. The first Tab completes to the longest
+    /// common prefix of the matching entries, repeated Tabs cycle through them.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn complete_address(&mut self) {
+        let address_bar = &mut self.address_bar;
+
+        // Cycle through the candidates from the previous completion
+        if !address_bar.completions.is_empty() {
+            let next = match address_bar.completion_index {
+                Some(index) => (index + 1) % address_bar.completions.len(),
+                None => 0,
+            };
+            address_bar.completion_index = Some(next);
+            address_bar.input = address_bar.completions[next].clone();
+            return;
+        }
+
+        let candidates = complete_path(&expand_home(&address_bar.input));
+
+        match candidates.len() {
+            0 => {}
+            1 => {
+                address_bar.input = candidates[0].clone();
+            }
+            _ => {
+                let prefix = longest_common_prefix(&candidates);
+                if prefix.len() > address_bar.input.len() {
+                    address_bar.input = prefix;
+                }
+                address_bar.completions = candidates;
+            }
+        }
+    }
+
+    /// Navigates to a path. Directories are opened as the `opened_dir`. For files,
+    /// the containing directory is opened, the file is opened in the viewer and
+    /// its row is selected. If the containing directory can't be read, the file
+    /// is opened with an empty file tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `path` - The path of the file or directory to navigate to
+    pub fn navigate_to_file(&mut self, path: &str) -> Result<(), std::io::Error> {
+        let file = FileNode::from_path(path, self.resolve_symlinks)?;
+        if file.is_dir {
+            return self.open_file(file);
+        }
+
+        let parent = file
+            .parent_folder
+            .as_ref()
+            .map(|parent| FileNode::from_path(parent, self.resolve_symlinks));
+
+        match parent {
+            Some(Ok(parent_node)) if fs::read_dir(&parent_node.absolute_path).is_ok() => {
+                self.open_file(parent_node)?;
+            }
+            _ => {
+                log::warn!("Could not read the parent of {}", file.absolute_path);
+                self.files = Vec::new();
+                self.page = 0;
+            }
+        }
+
+        self.open_file(file.clone())?;
+        self.select_path(&file.absolute_path);
+        // The directory may still be read in the background
+        if let Some(load) = &mut self.pending_dir_load {
+            load.select = Some(file.absolute_path.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Selects the file node with the given path and shows the page containing it
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `path` - The absolute path of the file node to select
+    fn select_path(&mut self, path: &str) {
+        self.selected_index = self.files.iter().position(|f| f.absolute_path == path);
+
+        if let Some(index) = self.selected_index
+            && let Some(position) = self.visible_files().iter().position(|(i, _)| *i == index)
+        {
+            self.page = position / self.page_size;
+        }
+    }
+
+    /// Turns to the page of the selected file node and scrolls it into view
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn scroll_to_selected(&mut self) -> Task<Action> {
+        let Some(position) = self
+            .selected_index
+            .and_then(|index| self.visible_files().iter().position(|(i, _)| *i == index))
+        else {
+            return Task::none();
+        };
+
+        self.page = position / self.page_size;
+        let rows = self.paged_files().len();
+        let y = (position % self.page_size) as f32 / rows.saturating_sub(1).max(1) as f32;
+        operation::snap_to(
+            FILE_TREE_SCROLLABLE_ID,
+            scrollable::RelativeOffset { x: 0.0, y },
+        )
+    }
+
+    fn open_child_file(&mut self, index: usize) -> Result<(), std::io::Error> {
+        let file = &self.files[index];
+        self.open_file(file.clone())
+    }
+
+    /// Opens a file or directory. This will set `opened_file` or `opened_dir` based on the file type.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instancee
+    /// * `file` - The file that should be opened from the file tree. Can be a `File` or `Directory` node.
+    fn open_file(&mut self, file: FileNode) -> Result<(), std::io::Error> {
+        log::debug!("Attempting to open, {:?}", file);
+
+        // Archives on disk are browsed as directories
+        if !file.is_dir && file.source == FileSource::Disk && is_archive(&file.absolute_path) {
+            return self.open_file(archive_root(&file));
+        }
+
+        if let Some(f) = &self.opened_file
+            && f.absolute_path == file.absolute_path
+        {
+            log::debug!("File is already opened - skipping");
+            return Ok(());
+        }
+
+        let opened_file = file.clone();
+        let absolute_path = opened_file.absolute_path.clone();
+
+        if opened_file.is_dir {
+            let previous = self.opened_dir.clone();
+            match self.open_dir(opened_file) {
+                // Large directories are counted before they are read, and ask before listing
+                Ok(()) => {
+                    if let Some(load) = &mut self.pending_dir_load {
+                        load.entry_limit = Some(self.large_dir_threshold);
+                        load.previous = Some(previous);
+                    }
+                }
+                Err(e) => log::error!("Could not open file: {}", e),
+            }
+        } else {
+            let file_type = determine_file_type(&file.absolute_path);
+            let mut behavior = self.associations.behavior_for(file_type.as_deref());
+
+            // Archives are read-only and cannot hand their entries to other programs
+            // or the image viewer yet, so show those as hex instead
+            if file.source != FileSource::Disk
+                && matches!(
+                    behavior,
+                    OpenBehavior::InternalImage | OpenBehavior::External
+                )
+            {
+                behavior = OpenBehavior::InternalHex;
+            }
+
+            if file.source == FileSource::Disk {
+                self.recent_files.add(&absolute_path);
+            }
+
+            if behavior == OpenBehavior::External {
+                self.launch_externally(&file);
+                return Ok(());
+            }
+
+            self.opened_file_details = match file.source {
+                FileSource::Disk => file_details(&absolute_path)
+                    .inspect_err(|e| log::warn!("Could not read the details of the file: {}", e))
+                    .ok(),
+                FileSource::Archive { .. } => None,
+            };
+            self.opened_file = Some(opened_file);
+            self.hex_view_offset = 0;
+            self.opened_file_view = self.associations.view_preferences_for(file_type.as_deref());
+            if file_type
+                .as_deref()
+                .is_none_or(|extension| !self.associations.remembers_view(extension))
+            {
+                self.opened_file_view.wrap =
+                    self.wrap_mode.wraps(&determine_language(&absolute_path));
+            }
+            self.load_opened_file(&file, behavior);
+        }
+
+        Ok(())
+    }
+
+    /// Describes the long-running operation in progress (if any), with its
+    /// percentage when it is known
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn busy_status(&self) -> Option<String> {
+        let percent = |done: u64, total: u64| (done * 100).checked_div(total).unwrap_or(0);
+
+        if let Some((copied, total)) = self.copy_progress {
+            return Some(format!("Copying… {}%", percent(copied, total)));
+        }
+        if let Some((extracted, total)) = self.extract_progress {
+            return Some(format!(
+                "Extracting… {}%",
+                percent(extracted as u64, total as u64)
+            ));
+        }
+        if let Some(file_hash) = self.file_hash.as_ref().filter(|h| h.digest.is_none()) {
+            let (hashed, total) = file_hash.progress;
+            return Some(format!("Hashing… {}%", percent(hashed, total)));
+        }
+        if self.filters.search_cancel.is_some() {
+            return Some(String::from("Searching…"));
+        }
+        if self.pending_dir_load.is_some() {
+            return Some(String::from("Loading…"));
+        }
+        None
+    }
+
+    /// Returns the settings remembered between sessions
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    #[cfg(feature = "serde")]
+    pub fn settings(&self) -> Settings {
+        // Directories within archives are not reopened
+        let last_dir = match self.opened_dir.source {
+            FileSource::Disk => Some(self.opened_dir.absolute_path.clone()),
+            FileSource::Archive { .. } => self.saved_settings.last_dir.clone(),
+        };
+
+        Settings {
+            last_dir,
+            window_size: self.window_size.or(self.saved_settings.window_size),
+            sort_mode: self.default_dir_view.sort_mode,
+            show_hidden: self.filters.show_hidden,
+            color_mode: self.color_mode,
+            row_density: self.row_density,
+            tree_indent: self.tree_indent,
+            wrap_mode: self.wrap_mode,
+            syntax_theme: self.syntax_theme.clone(),
+            external_editor: self.external_editor.clone(),
+            bookmarks: self.bookmarks.clone(),
+            dir_views: self.dir_views.clone(),
+            default_dir_view: Some(self.default_dir_view),
+            ..Settings::default()
+        }
+    }
+
+    /// Saves the settings if they changed since they were last saved
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    #[cfg(feature = "serde")]
+    pub fn save_settings(&mut self) {
+        let settings = self.settings();
+        if settings != self.saved_settings {
+            settings.save();
+            self.saved_settings = settings;
+        }
+    }
+
+    /// Returns whether the application is light or dark, following the system
+    /// unless the `color_mode` overrides it
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn effective_color_mode(&self) -> dark_light::Mode {
+        match self.color_mode {
+            ColorMode::System => self.system_color_mode,
+            ColorMode::Light => dark_light::Mode::Light,
+            ColorMode::Dark => dark_light::Mode::Dark,
+        }
+    }
+
+    /// Returns the (lowercase) extension of the opened file, if present
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    pub fn opened_file_extension(&self) -> Option<String> {
+        let file = self.opened_file.as_ref()?;
+        determine_file_type(&file.absolute_path).map(|e| e.to_lowercase())
+    }
+}
+
+/// The private helpers of the FileExplorerApp
+impl FileExplorerApp {
+    /// Returns a job building the JSON of the listing to export, to run on a
+    /// blocking thread. The shown files are serialized right away, while the
+    /// recursive listing is walked by the job. Returns `None` (with a status
+    /// message) for a recursive export within an archive.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `recursive` - A flag to export all of the descendants of the opened directory
+    #[cfg(feature = "serde")]
+    fn listing_json_job(
+        &mut self,
+        recursive: bool,
+    ) -> Option<Box<dyn FnOnce() -> Result<String, String> + Send>> {
+        if !recursive {
+            let json = listing_to_json(self.files.iter().filter(|f| f.matches_filters));
+            return Some(Box::new(move || json));
+        }
+
+        if self.opened_dir.source != FileSource::Disk {
+            self.status_message = Some(String::from(
+                "Folders within archives cannot be exported recursively yet",
+            ));
+            return None;
+        }
+
+        let root = PathBuf::from(&self.opened_dir.absolute_path);
+        let max_depth = self.max_walk_depth;
+        let follow_symlinks = self.follow_symlinks;
+        let resolve_symlinks = self.resolve_symlinks;
+        Some(Box::new(move || {
+            let nodes = walk_listing(&root, max_depth, follow_symlinks, resolve_symlinks);
+            listing_to_json(&nodes)
+        }))
+    }
+
+    /// Lists a directory and makes it the `opened_dir`
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `dir` - The directory to open
+    fn open_dir(&mut self, dir: FileNode) -> Result<(), std::io::Error> {
+        // Listed links keep their own path, they are only resolved once opened
+        let dir = if dir.is_symlink && self.resolve_symlinks && dir.source == FileSource::Disk {
+            FileNode::from_path(&dir.absolute_path, true)?
+        } else {
+            dir
+        };
+        let absolute_path = dir.absolute_path.clone();
+        // Return to the same place when the directory we leave is opened again
+        self.dir_scroll_offsets.insert(
+            self.opened_dir.absolute_path.clone(),
+            (self.page, self.file_tree_offset),
+        );
+        self.pending_scroll = Some(
+            self.dir_scroll_offsets
+                .get(&absolute_path)
+                .copied()
+                .unwrap_or_default(),
+        );
+        // Directories on disk may be slow to read (such as on network drives),
+        // so they are listed once read in the background
+        let files = match &dir.source {
+            FileSource::Disk => Vec::new(),
+            FileSource::Archive { .. } => list_dir(&dir)?,
+        };
+        self.cancel_dir_load();
+        if dir.source == FileSource::Disk {
+            self.pending_dir_load = Some(PendingDirLoad {
+                path: absolute_path.clone(),
+                cancel: None,
+                select: None,
+                entry_limit: None,
+                previous: None,
+            });
+        }
+
+        self.filters.file_name_search.clear();
+        self.filters.content_search.clear();
+        self.filters.content_matches = None;
+        self.new_folder_name = None;
+        self.renaming = None;
+        if let Some(search) = self.filters.search_cancel.take() {
+            search.cancel();
+        }
+        self.address_bar.input = absolute_path.clone();
+        self.address_bar.completions.clear();
+        self.address_bar.completion_index = None;
+        self.opened_dir = dir;
+        // Restore the filters remembered for this directory, if any
+        self.apply_dir_view(self.dir_view_for(&absolute_path));
+        self.files = files;
+        sort_nodes(&mut self.files, self.sort_mode);
+        self.showing_search_results = false;
+        self.search_results_truncated = false;
+        self.selected_index = None;
+        self.selection.clear();
+        self.dir_error = None;
+        self.apply_filters();
+        self.record_history(&absolute_path);
+
+        self.disk_space = match &self.opened_dir.source {
+            FileSource::Disk => disk_space(&absolute_path),
+            FileSource::Archive { archive_path, .. } => disk_space(archive_path),
+        };
+        Ok(())
+    }
+
+    /// Returns the filters currently applied to the file tree
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn current_dir_view(&self) -> DirViewPreferences {
+        DirViewPreferences {
+            kind: self.filters.kind,
+            search_mode: self.filters.search_mode,
+            sort_mode: self.sort_mode,
+        }
+    }
+
+    /// Returns the filters remembered for a directory, or those of the other
+    /// directories if it has none of its own
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `path` - The absolute path of the directory
+    fn dir_view_for(&self, path: &str) -> DirViewPreferences {
+        self.dir_views
+            .get(path)
+            .copied()
+            .unwrap_or(self.default_dir_view)
+    }
+
+    /// Applies remembered filters to the file tree. The files are not sorted or
+    /// filtered again.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `view` - The filters to apply
+    fn apply_dir_view(&mut self, view: DirViewPreferences) {
+        self.filters.kind = view.kind;
+        self.filters.search_mode = view.search_mode;
+        self.sort_mode = view.sort_mode;
+    }
+
+    /// Saves the current filters for the opened directory if it remembers its
+    /// own, or as the filters of the other directories otherwise
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn store_dir_view(&mut self) {
+        let view = self.current_dir_view();
+        match self.dir_views.get_mut(&self.opened_dir.absolute_path) {
+            Some(remembered) => *remembered = view,
+            None => self.default_dir_view = view,
+        }
+    }
+
+    /// Stores the state of the active tab in its entry of `tabs`
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn save_active_tab(&mut self) {
+        self.tabs[self.active_tab] = Tab {
+            opened_dir: self.opened_dir.clone(),
+            files: std::mem::take(&mut self.files),
+            file_name_search: self.filters.file_name_search.clone(),
+            kind: self.filters.kind,
+            search_mode: self.filters.search_mode,
+            selected_index: self.selected_index,
+            page: self.page,
+            showing_search_results: self.showing_search_results,
+            search_results_truncated: self.search_results_truncated,
+            loading: self.pending_dir_load.is_some(),
+            opened_file: self.opened_file.clone(),
+            opened_file_view: self.opened_file_view,
+        };
+        self.cancel_dir_load();
+    }
+
+    /// Makes the tab at the given index the active one, restoring its state
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `index` - The index of the tab
+    fn restore_tab(&mut self, index: usize) {
+        if let Some(search) = self.filters.search_cancel.take() {
+            search.cancel();
+        }
+
+        let tab = &mut self.tabs[index];
+        self.opened_dir = tab.opened_dir.clone();
+        self.files = std::mem::take(&mut tab.files);
+        // The tab may have been listed before the sort mode changed
+        let selected = tab
+            .selected_index
+            .and_then(|index| self.files.get(index))
+            .map(|f| f.absolute_path.clone());
+        self.collapse_all();
+        self.sort_mode = self.dir_view_for(&self.opened_dir.absolute_path).sort_mode;
+        sort_nodes(&mut self.files, self.sort_mode);
+        let tab = &self.tabs[index];
+        self.filters.file_name_search = tab.file_name_search.clone();
+        self.filters.kind = tab.kind;
+        self.filters.search_mode = tab.search_mode;
+        self.selected_index =
+            selected.and_then(|path| self.files.iter().position(|f| f.absolute_path == path));
+        self.selection.clear();
+        self.dir_error = None;
+        let page = tab.page;
+        self.showing_search_results = tab.showing_search_results;
+        self.search_results_truncated = tab.search_results_truncated;
+        self.active_tab = index;
+        // Read the directory again if the tab was left before it was read
+        self.cancel_dir_load();
+        if self.tabs[index].loading {
+            self.pending_dir_load = Some(PendingDirLoad {
+                path: self.opened_dir.absolute_path.clone(),
+                cancel: None,
+                select: None,
+                entry_limit: None,
+                previous: None,
+            });
+        }
+
+        // Content searches are not kept per tab, so the files they hid are shown again
+        self.filters.content_search.clear();
+        self.filters.content_matches = None;
+        self.apply_filters();
+        self.page = page;
+
+        self.address_bar.input = self.opened_dir.absolute_path.clone();
+        self.address_bar.completions.clear();
+        self.address_bar.completion_index = None;
+        self.disk_space = match &self.opened_dir.source {
+            FileSource::Disk => disk_space(&self.opened_dir.absolute_path),
+            FileSource::Archive { archive_path, .. } => disk_space(archive_path),
+        };
+
+        // Show the file opened in the tab, read again as it may have changed
+        let tab = &self.tabs[index];
+        let (opened_file, view) = (tab.opened_file.clone(), tab.opened_file_view);
+        match opened_file {
+            Some(file) => {
+                if let Err(e) = self.open_file(file) {
+                    log::error!("Could not open the file of the tab: {}", e);
+                }
+                self.opened_file_view = view;
+            }
+            None => {
+                let _ = self.post_update(Action::CloseFile);
+            }
+        }
+    }
+
+    /// Hides the children of all of the expanded directories, so the file tree
+    /// can be sorted as a flat list again
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn collapse_all(&mut self) {
+        self.files.retain(|f| f.depth == 0);
+        for file in &mut self.files {
+            file.expanded = false;
+            file.children = None;
+        }
+    }
+
+    /// Applies the settings saved by a previous session
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `settings` - The saved settings
+    #[cfg(feature = "serde")]
+    fn apply_settings(&mut self, settings: Settings) {
+        self.dir_views = settings.dir_views.clone();
+        // Settings saved before views were kept only have the sort mode
+        self.default_dir_view = settings.default_dir_view.unwrap_or(DirViewPreferences {
+            sort_mode: settings.sort_mode,
+            ..DirViewPreferences::default()
+        });
+        self.apply_dir_view(self.dir_view_for(&self.opened_dir.absolute_path));
+        self.filters.show_hidden = settings.show_hidden;
+        self.color_mode = settings.color_mode;
+        self.row_density = settings.row_density;
+        self.tree_indent = settings.tree_indent;
+        self.wrap_mode = settings.wrap_mode;
+        self.syntax_theme = settings.syntax_theme.clone();
+        self.external_editor = settings.external_editor.clone();
+        self.bookmarks = settings.bookmarks.clone();
+        self.window_size = settings.window_size;
+        sort_nodes(&mut self.files, self.sort_mode);
+        self.apply_filters();
+        self.saved_settings = settings;
+    }
+
+    /// Stops reading the directory of the `pending_dir_load`, if any
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn cancel_dir_load(&mut self) {
+        if let Some(cancel) = self.pending_dir_load.take().and_then(|load| load.cancel) {
+            cancel.cancel();
+        }
+    }
+
+    /// Adds a directory to the history after the `history_index`, dropping the
+    /// directories that could be gone forward to. Revisiting the directory at
+    /// the `history_index` (such as when moving through the history) adds nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `path` - The absolute path of the opened directory
+    fn record_history(&mut self, path: &str) {
+        if self
+            .history
+            .get(self.history_index)
+            .is_some_and(|p| p == path)
+        {
+            return;
+        }
+
+        self.history.truncate(self.history_index + 1);
+        self.history.push(String::from(path));
+        self.history_index = self.history.len() - 1;
+    }
+
+    /// Opens a directory of the history without changing the history itself
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `index` - The position of the directory within the history
+    fn visit_history(&mut self, index: usize) {
+        let path = self.history[index].clone();
+        let previous_index = self.history_index;
+        self.history_index = index;
+
+        let opened =
+            FileNode::from_path(&path, self.resolve_symlinks).and_then(|dir| self.open_file(dir));
+        if let Err(e) = opened {
+            self.history_index = previous_index;
+            self.status_message = Some(format!("Could not go to {}: {}", path, e));
+        }
+    }
+
+    /// Points the opened file to its new path after it (or a folder containing
+    /// it) was renamed, so the viewer keeps showing it
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `source` - The path of the renamed file or folder before the rename
+    /// * `destination` - The path of the renamed file or folder after the rename
+    fn follow_rename(&mut self, source: &Path, destination: &Path) {
+        let Some(opened) = &self.opened_file else {
+            return;
+        };
+        let Ok(relative) = Path::new(&opened.absolute_path).strip_prefix(source) else {
+            return;
+        };
+
+        let path = destination.join(relative);
+        match FileNode::from_path(&path.to_string_lossy(), self.resolve_symlinks) {
+            Ok(file) => self.opened_file = Some(file),
+            Err(e) => log::warn!("Could not read the renamed file {}: {}", path.display(), e),
+        }
+    }
+
+    /// Extracts an archive into a new folder named after it, within `folder`
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `archive` - The path of the archive
+    /// * `folder` - The folder to create the new folder in
+    fn extract_archive(&mut self, archive: String, folder: &Path) -> Task<Action> {
+        if !is_extractable(&archive) {
+            self.status_message = Some(String::from("Only archives can be extracted"));
+            return Task::none();
+        }
+
+        let archive = PathBuf::from(archive);
+        let file_name = archive
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        // Append a suffix rather than extracting into an existing folder
+        let destination = unique_destination(folder, &extraction_folder_name(&file_name));
+
+        self.extract_progress = Some((0, 0));
+        self.status_message = Some(format!("Extracting {}...", file_name));
+        Task::run(
+            extract_with_progress(archive, destination),
+            Action::ExtractProgress,
+        )
+    }
+
+    /// Stops measuring the directory of the file info modal (if in progress)
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn cancel_dir_size(&mut self) {
+        if let Some(cancel) = self.file_info_dir_size_cancel.take() {
+            cancel.cancel();
+        }
+    }
+
+    /// Stops the hashing in progress (if any) and forgets the last hash
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn cancel_hashing(&mut self) {
+        if let Some(handle) = self.file_hash_handle.take() {
+            handle.abort();
+        }
+        self.file_hash = None;
+    }
+
+    /// Compares the `opened_dir` against another directory off the UI thread
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `right` - The directory to compare against
+    fn compare_with(&mut self, right: PathBuf) -> Task<Action> {
+        let left = PathBuf::from(&self.opened_dir.absolute_path);
+        let max_depth = self.max_walk_depth;
+        let compare_contents = self.compare_contents;
+        self.status_message = Some(format!("Comparing with {}...", right.display()));
+
+        Task::perform(
+            tokio::task::spawn_blocking(move || {
+                compare_dirs(&left, &right, max_depth, compare_contents)
+            }),
+            |result| match result {
+                Ok(comparison) => Action::ComparisonFinished(comparison),
+                Err(e) => {
+                    log::error!("Could not compare directories: {}", e);
+                    Action::CloseComparison
+                }
+            },
+        )
+    }
+
+    /// Searches the subfolders of the `opened_dir` for the file name search off
+    /// the UI thread, stopping at `max_search_results`
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn search_recursively(&mut self) -> Task<Action> {
+        // Stop the previous search, whose results would be discarded
+        if let Some(previous) = self.filters.search_cancel.take() {
+            previous.cancel();
+        }
+        let cancel = CancelToken::default();
+        self.filters.search_cancel = Some(cancel.clone());
+
+        let search_file_name = self.filters.file_name_search.clone();
+        let query = self.name_query();
+        // An invalid pattern leaves the list unfiltered instead of searching
+        if self.filters.search_mode == SearchMode::Regex && query.regex.is_none() {
+            self.filters.search_cancel = None;
+            self.apply_filters();
+            return Task::none();
+        }
+        let root = self.opened_dir.absolute_path.clone();
+        let search_mode = self.filters.search_mode;
+        let max_depth = self.max_walk_depth;
+        let follow_symlinks = self.follow_symlinks;
+        let max_results = self.max_search_results;
+
+        Task::perform(
+            tokio::task::spawn_blocking(move || {
+                let matcher = fuzzy_matcher(query.case_sensitive);
+                search_tree(
+                    &root,
+                    max_depth,
+                    follow_symlinks,
+                    max_results,
+                    &cancel,
+                    |name| match search_mode {
+                        SearchMode::Substring => query.matches(name),
+                        SearchMode::Regex => query
+                            .regex
+                            .as_ref()
+                            .is_some_and(|regex| regex.is_match(name)),
+                        SearchMode::Fuzzy => matcher
+                            .fuzzy_match(name, &query.search)
+                            .is_some_and(|score| score >= MIN_FUZZY_SCORE),
+                    },
+                )
+            }),
+            move |result| match result {
+                Ok(results) => Action::RecursiveSearchFinished(search_file_name, results),
+                Err(e) => {
+                    log::error!("Could not search recursively: {}", e);
+                    Action::RecursiveSearchFinished(
+                        search_file_name,
+                        Cancelable::Completed(SearchResults {
+                            nodes: Vec::new(),
+                            truncated: false,
+                        }),
+                    )
+                }
+            },
+        )
+    }
+
+    /// Reads the contents of the opened file for the given behavior, along with
+    /// its type and statistics
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `file` - The opened file
+    /// * `behavior` - How the file is displayed
+    fn load_opened_file(&mut self, file: &FileNode, behavior: OpenBehavior) {
+        // Images that cannot be decoded are shown in the hex viewer instead
+        self.opened_image = None;
+        let behavior = match behavior {
+            OpenBehavior::InternalImage => match ::image::image_dimensions(&file.absolute_path) {
+                Ok((width, height)) => {
+                    self.opened_image = Some(OpenedImage {
+                        handle: image::Handle::from_path(&file.absolute_path),
+                        width,
+                        height,
+                    });
+                    behavior
+                }
+                Err(e) => {
+                    log::warn!("Could not decode the image {}: {}", file.absolute_path, e);
+                    self.status_message = Some(format!(
+                        "Could not show {} as an image: {}",
+                        file.file_name, e
+                    ));
+                    OpenBehavior::InternalHex
+                }
+            },
+            _ => behavior,
+        };
+        self.opened_file_behavior = behavior;
+        self.opened_file_type = None;
+        self.opened_file_stats = None;
+        self.opened_file_encoding = None;
+        self.opened_file_read_limit = TEXT_VIEW_CHUNK_BYTES;
+        self.opened_file_truncated = false;
+        self.opened_file_binary = false;
+        self.opened_file_contents = match behavior {
+            OpenBehavior::InternalText => self.read_text(file, None),
+            OpenBehavior::InternalHex => {
+                read_hex_dump(file, self.hex_view_offset, self.hex_view_window)
+            }
+            // Images are loaded by the image viewer from the path
+            OpenBehavior::InternalImage | OpenBehavior::External => Ok(String::from("")),
+        };
+
+        match &self.opened_file_contents {
+            // Ignore errors when reading file contents
+            Err(_) => {}
+            Ok(contents) => {
+                if behavior == OpenBehavior::InternalText {
+                    let mut language = determine_language(&file.absolute_path);
+                    // Scripts without an extension name their language in a shebang
+                    if determine_file_type(&file.absolute_path).is_none()
+                        && language == "txt"
+                        && let Some(shebang) = contents.lines().next().and_then(shebang_language)
+                    {
+                        language = shebang;
+                    }
+                    self.opened_file_type = Some(language);
+                    self.opened_file_stats = self.compute_file_stats();
+                }
+            }
+        }
+        self.highlighted = None;
+        self.highlight_opened_file();
+        self.update_find_matches();
+    }
+
+    /// Highlights the opened file, unless it is already highlighted with the
+    /// current theme
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn highlight_opened_file(&mut self) {
+        let (Some(file), Ok(contents)) = (&self.opened_file, &self.opened_file_contents) else {
+            self.highlighted = None;
+            return;
+        };
+
+        let theme_name = self.syntax_theme.theme_name(self.effective_color_mode());
+        let up_to_date = self.highlighted.as_ref().is_some_and(|highlighted| {
+            highlighted.path == file.absolute_path && highlighted.theme == theme_name
+        });
+        if up_to_date {
+            return;
+        }
+
+        self.highlighted = Some(highlight_file(
+            &file.absolute_path,
+            contents,
+            self.opened_file_type.as_deref(),
+            &self.highlighting.syntax_set,
+            &self.highlighting.theme_set,
+            theme_name,
+        ));
+    }
+
+    /// Reads the bytes of the opened file at the `hex_view_offset` again, if it
+    /// is shown in the hex viewer
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn reload_hex_view(&mut self) {
+        let Some(file) = &self.opened_file else {
+            return;
+        };
+        if self.opened_file_behavior != OpenBehavior::InternalHex {
+            return;
+        }
+
+        self.opened_file_contents = read_hex_dump(file, self.hex_view_offset, self.hex_view_window);
+        self.highlighted = None;
+        self.highlight_opened_file();
+        self.update_find_matches();
+    }
+
+    /// Finds the lines of the opened file containing the `find_query` (ignoring case)
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn update_find_matches(&mut self) {
+        self.find_matches.clear();
+        self.find_current = None;
+
+        let query = self.find_query.to_lowercase();
+        let Ok(contents) = &self.opened_file_contents else {
+            return;
+        };
+        if query.is_empty() {
+            return;
+        }
+
+        self.find_matches = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| line.to_lowercase().contains(&query))
+            .map(|(index, _)| index)
+            .collect();
+    }
+
+    /// Updates the view remembered for the type of the opened file, if one is
+    /// remembered, so it follows the changes made while viewing
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn remember_opened_view(&mut self) {
+        if let Some(extension) = self.opened_file_extension()
+            && self.associations.remembers_view(&extension)
+        {
+            self.associations.remember_view(
+                &extension,
+                self.opened_file_behavior,
+                self.opened_file_view,
+            );
+        }
+    }
+
+    /// Reads a text file up to the `opened_file_read_limit`, decoding it with the
+    /// given encoding or the detected one. Sets `opened_file_encoding` to the
+    /// encoding that was used and `opened_file_truncated` if the file is longer.
+    /// When detecting the encoding, binary files are not decoded and set
+    /// `opened_file_binary` instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `file` - The file to read
+    /// * `encoding` - The encoding to decode with, or `None` to detect it
+    fn read_text(
+        &mut self,
+        file: &FileNode,
+        encoding: Option<TextEncoding>,
+    ) -> Result<String, std::io::Error> {
+        // Read one byte more than the limit to tell if the file is longer
+        let mut bytes = read_bytes(file, self.opened_file_read_limit + 1)?;
+        self.opened_file_binary = encoding.is_none() && is_binary(&bytes);
+        if self.opened_file_binary {
+            self.opened_file_truncated = false;
+            self.opened_file_encoding = None;
+            return Ok(String::new());
+        }
+        self.opened_file_truncated = bytes.len() as u64 > self.opened_file_read_limit;
+        bytes.truncate(self.opened_file_read_limit as usize);
+        let (text, used) = decode_text(&bytes, encoding);
+        self.opened_file_encoding = Some(used);
+        Ok(text)
+    }
+
+    /// Computes the statistics of the opened text file
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn compute_file_stats(&self) -> Option<FileStats> {
+        let contents = self.opened_file_contents.as_ref().ok()?;
+        let language = find_syntax(
+            &self.highlighting.syntax_set,
+            self.opened_file_type.as_deref(),
+        )
+        .name
+        .clone();
+
+        Some(FileStats {
+            lines: contents.lines().count(),
+            characters: contents.chars().count(),
+            language,
+        })
+    }
+}
+
+/// Reads a window of the bytes of a file and formats it as a hex dump
+///
+/// # Arguments
+///
+/// * `file` - The file to read
+/// * `offset` - The offset of the first byte of the window
+/// * `window` - The number of bytes to read
+fn read_hex_dump(file: &FileNode, offset: u64, window: u64) -> Result<String, std::io::Error> {
+    let bytes = read_bytes_at(file, offset, window)?;
+    Ok(hex_dump(&bytes, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::temp_dir;
+
+    #[test]
+    fn read_text_truncates_long_files() {
+        let dir = temp_dir("read-text-truncates");
+        let path = dir.join("long.txt");
+        fs::write(&path, "a".repeat(4096)).unwrap();
+        let file = FileNode::from_path(&path.to_string_lossy(), false).unwrap();
+
+        let mut app = FileExplorerApp::default();
+        app.opened_file_read_limit = 1024;
+        let text = app.read_text(&file, None).unwrap();
+
+        assert_eq!(text.len(), 1024);
+        assert!(app.opened_file_truncated);
+    }
+
+    #[test]
+    fn dir_view_falls_back_to_the_default_view() {
+        let mut app = FileExplorerApp::default();
+        app.default_dir_view = DirViewPreferences {
+            sort_mode: SortMode::SizeDesc,
+            ..DirViewPreferences::default()
+        };
+        let remembered = DirViewPreferences {
+            kind: KindFilter::FoldersOnly,
+            search_mode: SearchMode::Fuzzy,
+            sort_mode: SortMode::ModifiedDesc,
+        };
+        app.dir_views
+            .insert(String::from("/remembered"), remembered);
+
+        assert_eq!(app.dir_view_for("/remembered"), remembered);
+        assert_eq!(app.dir_view_for("/other"), app.default_dir_view);
+    }
+
+    #[test]
+    fn store_dir_view_updates_the_remembered_view_only() {
+        let mut app = FileExplorerApp::default();
+        let path = app.opened_dir.absolute_path.clone();
+        app.dir_views
+            .insert(path.clone(), DirViewPreferences::default());
+        let default_dir_view = app.default_dir_view;
+
+        app.sort_mode = SortMode::NameDesc;
+        app.store_dir_view();
+
+        assert_eq!(app.dir_views[&path].sort_mode, SortMode::NameDesc);
+        assert_eq!(app.default_dir_view, default_dir_view);
+
+        app.dir_views.clear();
+        app.sort_mode = SortMode::SizeDesc;
+        app.store_dir_view();
+
+        assert_eq!(app.default_dir_view.sort_mode, SortMode::SizeDesc);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn dir_views_are_restored_from_the_settings() {
+        let mut app = FileExplorerApp::default();
+        let view = DirViewPreferences {
+            kind: KindFilter::FilesOnly,
+            search_mode: SearchMode::Regex,
+            sort_mode: SortMode::NameDesc,
+        };
+        app.dir_views.insert(String::from("/remembered"), view);
+        let settings = app.settings();
+
+        let mut restored = FileExplorerApp::default();
+        restored.apply_settings(settings);
+
+        assert_eq!(restored.dir_view_for("/remembered"), view);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn older_settings_keep_their_sort_mode() {
+        let settings = Settings {
+            sort_mode: SortMode::ModifiedDesc,
+            default_dir_view: None,
+            ..Settings::default()
+        };
+
+        let mut app = FileExplorerApp::default();
+        app.apply_settings(settings);
+
+        assert_eq!(app.default_dir_view.sort_mode, SortMode::ModifiedDesc);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn row_layout_is_restored_from_the_settings() {
+        let mut app = FileExplorerApp::default();
+        app.row_density = RowDensity::Compact;
+        app.tree_indent = TreeIndent::Wide;
+        let settings = app.settings();
+
+        let mut restored = FileExplorerApp::default();
+        restored.apply_settings(settings);
+
+        assert_eq!(restored.row_density, RowDensity::Compact);
+        assert_eq!(restored.tree_indent, TreeIndent::Wide);
+    }
+
+    #[test]
+    fn read_text_reads_short_files_whole() {
+        let dir = temp_dir("read-text-whole");
+        let path = dir.join("short.txt");
+        fs::write(&path, "a".repeat(1024)).unwrap();
+        let file = FileNode::from_path(&path.to_string_lossy(), false).unwrap();
+
+        let mut app = FileExplorerApp::default();
+        app.opened_file_read_limit = 1024;
+        let text = app.read_text(&file, None).unwrap();
+
+        assert_eq!(text.len(), 1024);
+        assert!(!app.opened_file_truncated);
     }
 }
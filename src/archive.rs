@@ -0,0 +1,439 @@
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, Read},
+    path::{Component, MAIN_SEPARATOR, Path, PathBuf},
+};
+
+use flate2::read::GzDecoder;
+#[cfg(feature = "iced-ui")]
+use iced::futures::{SinkExt, Stream, channel::mpsc, executor::block_on};
+use zip::ZipArchive;
+
+use crate::fs_utils::{FileNode, FileSource, SortMode, human_size, sort_nodes};
+
+/// The extensions of the archives that can be browsed as directories
+const ARCHIVE_EXTENSIONS: [&str; 1] = ["zip"];
+
+/// The suffixes of the archives that can be extracted, longest first so that
+/// `.tar.gz` is matched before `.gz` would be
+const EXTRACTABLE_SUFFIXES: [&str; 4] = [".tar.gz", ".tgz", ".tar", ".zip"];
+
+/// The events emitted while an archive is being extracted
+#[cfg(feature = "iced-ui")]
+#[derive(Debug, Clone)]
+pub enum ExtractEvent {
+    /// The number of entries extracted so far and the total number of entries
+    Progress { extracted: usize, total: usize },
+    /// The extraction completed with the folder extracted to, or failed with an error
+    Finished(Result<PathBuf, String>),
+}
+
+/// The separator used between the components of an entry path within an archive
+const ENTRY_SEPARATOR: char = '/';
+
+/// Returns true if the file at the given path is an archive that can be browsed
+///
+/// # Arguments
+///
+/// * `path` - The path to the file
+pub fn is_archive(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| ARCHIVE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Returns the suffix of an archive that can be extracted, if the path has one
+///
+/// # Arguments
+///
+/// * `path` - The path to the file
+fn extractable_suffix(path: &str) -> Option<&'static str> {
+    let lowercase = path.to_lowercase();
+    EXTRACTABLE_SUFFIXES
+        .into_iter()
+        .find(|suffix| lowercase.ends_with(suffix))
+}
+
+/// Returns true if the file at the given path is an archive that can be extracted
+///
+/// # Arguments
+///
+/// * `path` - The path to the file
+pub fn is_extractable(path: &str) -> bool {
+    extractable_suffix(path).is_some()
+}
+
+/// Returns the name of the folder an archive is extracted to: its file name
+/// without the archive suffix (e.g. `photos` for `photos.tar.gz`)
+///
+/// # Arguments
+///
+/// * `file_name` - The file name of the archive
+pub fn extraction_folder_name(file_name: &str) -> String {
+    match extractable_suffix(file_name) {
+        Some(suffix) if file_name.len() > suffix.len() => {
+            String::from(&file_name[..file_name.len() - suffix.len()])
+        }
+        _ => format!("{} contents", file_name),
+    }
+}
+
+/// Extracts an archive into a folder off the async runtime, reporting the
+/// progress as it goes. The folder must not exist yet. Entries that would be
+/// written outside of the folder (e.g. `../evil`) fail the extraction.
+/// The stream ends with an [`ExtractEvent::Finished`] event.
+///
+/// # Arguments
+///
+/// * `archive` - The archive to extract
+/// * `destination` - The folder to extract the archive into
+#[cfg(feature = "iced-ui")]
+pub fn extract_with_progress(
+    archive: PathBuf,
+    destination: PathBuf,
+) -> impl Stream<Item = ExtractEvent> {
+    iced::stream::channel(
+        16,
+        move |mut sender: mpsc::Sender<ExtractEvent>| async move {
+            let mut progress = sender.clone();
+            let target = destination.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let mut report = |extracted, total| {
+                    let _ = block_on(progress.send(ExtractEvent::Progress { extracted, total }));
+                };
+                extract(&archive, &target, &mut report)
+            })
+            .await;
+
+            let result = match result {
+                Ok(Ok(())) => Ok(destination),
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = sender.send(ExtractEvent::Finished(result)).await;
+        },
+    )
+}
+
+/// Extracts an archive into a new folder, calling `report` with the number of
+/// entries extracted so far and the total number of entries
+///
+/// # Arguments
+///
+/// * `archive` - The archive to extract
+/// * `destination` - The folder to extract the archive into
+/// * `report` - Called after each entry
+pub fn extract(
+    archive: &Path,
+    destination: &Path,
+    report: &mut impl FnMut(usize, usize),
+) -> Result<(), io::Error> {
+    let suffix = extractable_suffix(&archive.to_string_lossy())
+        .ok_or_else(|| io::Error::other("This kind of archive can't be extracted"))?;
+    fs::create_dir(destination)?;
+
+    let result = match suffix {
+        ".zip" => extract_zip(archive, destination, report),
+        ".tar" => extract_tar(|| Ok(File::open(archive)?), destination, report),
+        _ => extract_tar(
+            || Ok(GzDecoder::new(File::open(archive)?)),
+            destination,
+            report,
+        ),
+    };
+
+    // Don't leave a partially extracted archive behind. The folder was created
+    // above, so nothing else is removed.
+    if result.is_err() {
+        let _ = fs::remove_dir_all(destination);
+    }
+    result
+}
+
+/// Extracts a zip archive into a folder
+///
+/// # Arguments
+///
+/// * `archive` - The archive to extract
+/// * `destination` - The folder to extract the archive into
+/// * `report` - Called after each entry
+fn extract_zip(
+    archive: &Path,
+    destination: &Path,
+    report: &mut impl FnMut(usize, usize),
+) -> Result<(), io::Error> {
+    let mut archive = ZipArchive::new(File::open(archive)?).map_err(io::Error::other)?;
+    let total = archive.len();
+
+    for i in 0..total {
+        let mut entry = archive.by_index(i).map_err(io::Error::other)?;
+        let path = contained_path(destination, Path::new(entry.name()))?;
+
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            // Archives listing the same file twice are reported, not overwritten
+            let mut file = File::create_new(&path)?;
+            io::copy(&mut entry, &mut file)?;
+        }
+        report(i + 1, total);
+    }
+
+    Ok(())
+}
+
+/// Extracts a tar archive (compressed or not) into a folder. The archive is
+/// read twice: once to count its entries, then to extract them.
+///
+/// # Arguments
+///
+/// * `open` - Opens a reader over the tar stream
+/// * `destination` - The folder to extract the archive into
+/// * `report` - Called after each entry
+fn extract_tar<R: Read>(
+    open: impl Fn() -> Result<R, io::Error>,
+    destination: &Path,
+    report: &mut impl FnMut(usize, usize),
+) -> Result<(), io::Error> {
+    let total = tar::Archive::new(open()?).entries()?.count();
+
+    let mut archive = tar::Archive::new(open()?);
+    for (i, entry) in archive.entries()?.enumerate() {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        contained_path(destination, &entry_path)?;
+
+        // `unpack_in` also refuses to write through symbolic links leaving the folder
+        if !entry.unpack_in(destination)? {
+            return Err(io::Error::other(format!(
+                "{} would be extracted outside of the folder",
+                entry_path.display()
+            )));
+        }
+        report(i + 1, total);
+    }
+
+    Ok(())
+}
+
+/// Returns the path an entry is extracted to, or an error if the entry's path
+/// is absolute or climbs out of the folder with `..` (a "zip slip")
+///
+/// # Arguments
+///
+/// * `destination` - The folder the archive is extracted into
+/// * `entry_path` - The path of the entry within the archive
+fn contained_path(destination: &Path, entry_path: &Path) -> Result<PathBuf, io::Error> {
+    let mut path = destination.to_path_buf();
+
+    for component in entry_path.components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(io::Error::other(format!(
+                    "{} would be extracted outside of the folder",
+                    entry_path.display()
+                )));
+            }
+        }
+    }
+
+    Ok(path)
+}
+
+/// Returns the virtual directory node for the root of an archive on disk
+///
+/// # Arguments
+///
+/// * `archive` - The node of the archive file
+#[cfg(feature = "iced-ui")]
+pub fn archive_root(archive: &FileNode) -> FileNode {
+    FileNode {
+        is_dir: true,
+        source: FileSource::Archive {
+            archive_path: archive.absolute_path.clone(),
+            entry_path: String::new(),
+        },
+        git_status: None,
+        ..archive.clone()
+    }
+}
+
+/// Returns the node of the directory containing a virtual archive directory.
+/// The root of an archive returns `None`, as its parent is on disk.
+///
+/// # Arguments
+///
+/// * `archive_path` - The path of the archive on disk
+/// * `entry_path` - The path of the directory within the archive
+#[cfg(feature = "iced-ui")]
+pub fn archive_parent(archive_path: &str, entry_path: &str) -> Option<FileNode> {
+    let trimmed = entry_path.trim_end_matches(ENTRY_SEPARATOR);
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let parent = match trimmed.rfind(ENTRY_SEPARATOR) {
+        Some(i) => &trimmed[..=i],
+        None => "",
+    };
+
+    if parent.is_empty() {
+        let archive = FileNode::from_relative_path(&String::from(archive_path)).ok()?;
+        return Some(archive_root(&archive));
+    }
+
+    Some(archive_node(archive_path, parent, true, 0, String::new()))
+}
+
+/// Lists the entries directly within a directory of an archive
+///
+/// # Arguments
+///
+/// * `archive_path` - The path of the archive on disk
+/// * `entry_path` - The path of the directory within the archive (empty for the root)
+pub fn read_archive_dir(archive_path: &str, entry_path: &str) -> Result<Vec<FileNode>, io::Error> {
+    let mut archive = ZipArchive::new(File::open(archive_path)?).map_err(io::Error::other)?;
+    let mut nodes: Vec<FileNode> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(io::Error::other)?;
+        let Some(rest) = entry.name().strip_prefix(entry_path) else {
+            continue;
+        };
+
+        // Entries nested deeper show up as the directory containing them, as
+        // archives do not always have entries for their directories
+        let (name, is_dir) = match rest.split_once(ENTRY_SEPARATOR) {
+            Some((name, _)) => (name, true),
+            None => (rest, false),
+        };
+
+        if name.is_empty() || !seen.insert(String::from(name)) {
+            continue;
+        }
+
+        let (child_path, size_bytes) = if is_dir {
+            (format!("{}{}{}", entry_path, name, ENTRY_SEPARATOR), 0)
+        } else {
+            (format!("{}{}", entry_path, name), entry.size())
+        };
+
+        let modified_at = entry
+            .last_modified()
+            .map(|t| {
+                format!(
+                    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                    t.year(),
+                    t.month(),
+                    t.day(),
+                    t.hour(),
+                    t.minute(),
+                    t.second()
+                )
+            })
+            .unwrap_or_default();
+
+        nodes.push(archive_node(
+            archive_path,
+            &child_path,
+            is_dir,
+            size_bytes,
+            modified_at,
+        ));
+    }
+
+    sort_nodes(&mut nodes, SortMode::default());
+
+    Ok(nodes)
+}
+
+/// Reads the contents of a file within an archive
+///
+/// # Arguments
+///
+/// * `archive_path` - The path of the archive on disk
+/// * `entry_path` - The path of the file within the archive
+/// * `limit` - The maximum number of bytes to read
+pub fn read_archive_entry(
+    archive_path: &str,
+    entry_path: &str,
+    limit: u64,
+) -> Result<Vec<u8>, io::Error> {
+    let mut archive = ZipArchive::new(File::open(archive_path)?).map_err(io::Error::other)?;
+    let entry = archive.by_name(entry_path).map_err(io::Error::other)?;
+
+    let mut bytes = Vec::new();
+    entry.take(limit).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Builds the node of an entry within an archive. Its absolute path is virtual,
+/// made of the archive's path followed by the entry's path.
+///
+/// # Arguments
+///
+/// * `archive_path` - The path of the archive on disk
+/// * `entry_path` - The path of the entry within the archive
+/// * `is_dir` - A flag to indicate if the entry is a directory
+/// * `size_bytes` - The size of the entry in bytes
+/// * `modified_at` - When the entry was last modified
+fn archive_node(
+    archive_path: &str,
+    entry_path: &str,
+    is_dir: bool,
+    size_bytes: u64,
+    modified_at: String,
+) -> FileNode {
+    let virtual_path = |path: &str| {
+        let path = path.trim_end_matches(ENTRY_SEPARATOR);
+        if path.is_empty() {
+            String::from(archive_path)
+        } else {
+            format!("{}{}{}", archive_path, MAIN_SEPARATOR, path)
+        }
+    };
+
+    let trimmed = entry_path.trim_end_matches(ENTRY_SEPARATOR);
+    let (parent, file_name) = match trimmed.rsplit_once(ENTRY_SEPARATOR) {
+        Some((parent, name)) => (parent, name),
+        None => ("", trimmed),
+    };
+
+    FileNode {
+        file_name: String::from(file_name),
+        absolute_path: virtual_path(trimmed),
+        parent_folder: Some(virtual_path(parent)),
+        is_dir,
+        matches_filters: true,
+        match_score: None,
+        match_indices: Vec::new(),
+        size_bytes,
+        file_size: if is_dir {
+            String::new()
+        } else {
+            human_size(size_bytes)
+        },
+        created_at: String::new(),
+        modified_at,
+        modified: None,
+        accessed_at: String::new(),
+        git_status: None,
+        source: FileSource::Archive {
+            archive_path: String::from(archive_path),
+            entry_path: String::from(entry_path),
+        },
+        is_symlink: false,
+        link_target: None,
+        children: None,
+        expanded: false,
+        depth: 0,
+    }
+}
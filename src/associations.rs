@@ -0,0 +1,242 @@
+use std::{collections::BTreeMap, fmt};
+
+/// The default font size of the file contents in the viewer
+pub const DEFAULT_VIEW_FONT_SIZE: f32 = 16.0;
+
+/// Describes how a file should be opened when it is clicked in the file tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenBehavior {
+    /// Open in the internal text viewer with syntax highlighting
+    InternalText,
+    /// Open in the internal image viewer
+    InternalImage,
+    /// Open in the internal hex viewer
+    InternalHex,
+    /// Open with the system default application
+    External,
+}
+
+impl OpenBehavior {
+    /// All of the behaviors, in the order they are shown to the user
+    pub const ALL: [OpenBehavior; 4] = [
+        OpenBehavior::InternalText,
+        OpenBehavior::InternalImage,
+        OpenBehavior::InternalHex,
+        OpenBehavior::External,
+    ];
+}
+
+impl fmt::Display for OpenBehavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            OpenBehavior::InternalText => "Text Viewer",
+            OpenBehavior::InternalImage => "Image Viewer",
+            OpenBehavior::InternalHex => "Hex Viewer",
+            OpenBehavior::External => "External Application",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Describes how the viewer presents the contents of a file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewPreferences {
+    /// A flag to indicate if long lines wrap instead of scrolling horizontally
+    pub wrap: bool,
+    /// The font size of the file contents
+    pub font_size: f32,
+}
+
+/// The default view preferences
+impl Default for ViewPreferences {
+    fn default() -> Self {
+        ViewPreferences {
+            wrap: true,
+            font_size: DEFAULT_VIEW_FONT_SIZE,
+        }
+    }
+}
+
+/// The languages of prose rather than code, whose long lines wrap by default
+const PROSE_LANGUAGES: [&str; 6] = ["txt", "md", "markdown", "rst", "log", "adoc"];
+
+/// Whether long lines wrap in the viewer, for files without remembered view preferences
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WrapMode {
+    /// Wrap prose (such as plain text), but not code
+    #[default]
+    Automatic,
+    /// Always wrap long lines
+    Always,
+    /// Never wrap long lines, scrolling horizontally instead
+    Never,
+}
+
+/// Wrap Mode methods
+impl WrapMode {
+    /// All of the wrap modes, in the order they are shown to the user
+    pub const ALL: [WrapMode; 3] = [WrapMode::Automatic, WrapMode::Always, WrapMode::Never];
+
+    /// Returns true if long lines of files in the given language wrap
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The wrap mode
+    /// * `language` - The language the file is highlighted as
+    pub fn wraps(&self, language: &str) -> bool {
+        match self {
+            WrapMode::Automatic => PROSE_LANGUAGES.contains(&language),
+            WrapMode::Always => true,
+            WrapMode::Never => false,
+        }
+    }
+}
+
+impl fmt::Display for WrapMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            WrapMode::Automatic => "Prose Only",
+            WrapMode::Always => "Always",
+            WrapMode::Never => "Never",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Maps file extensions to the [`OpenBehavior`] used to open them
+#[derive(Debug, Clone)]
+pub struct FileAssociations {
+    /// The behavior for each (lowercase) file extension
+    pub by_extension: BTreeMap<String, OpenBehavior>,
+    /// The view preferences remembered for each (lowercase) file extension
+    pub view_preferences: BTreeMap<String, ViewPreferences>,
+    /// The behavior for files with an unknown or no extension
+    pub fallback: OpenBehavior,
+}
+
+/// The default associations
+impl Default for FileAssociations {
+    fn default() -> Self {
+        let mut by_extension = BTreeMap::new();
+
+        let defaults: [(&[&str], OpenBehavior); 3] = [
+            (
+                &["png", "jpg", "jpeg", "gif", "bmp", "webp", "ico"],
+                OpenBehavior::InternalImage,
+            ),
+            (
+                &["bin", "exe", "dll", "so", "dylib", "o", "a", "class", "dat"],
+                OpenBehavior::InternalHex,
+            ),
+            (
+                &[
+                    "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "mp3", "wav",
+                    "flac", "mp4", "mkv", "mov", "avi",
+                ],
+                OpenBehavior::External,
+            ),
+        ];
+
+        for (extensions, behavior) in defaults {
+            for extension in extensions {
+                by_extension.insert(extension.to_string(), behavior);
+            }
+        }
+
+        FileAssociations {
+            by_extension,
+            view_preferences: BTreeMap::new(),
+            // Unknown files are most often text (e.g. config files), so keep
+            // them in the internal viewer
+            fallback: OpenBehavior::InternalText,
+        }
+    }
+}
+
+/// File Association methods
+impl FileAssociations {
+    /// Returns the behavior to use for a file with the given extension
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The extension of the file, if present
+    pub fn behavior_for(&self, extension: Option<&str>) -> OpenBehavior {
+        extension
+            .and_then(|e| self.by_extension.get(&e.to_lowercase()))
+            .copied()
+            .unwrap_or(self.fallback)
+    }
+
+    /// Sets the behavior for the given extension
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The extension to associate. A leading `.` is ignored
+    /// * `behavior` - The behavior to use for the extension
+    pub fn set(&mut self, extension: &str, behavior: OpenBehavior) {
+        let extension = extension.trim().trim_start_matches('.').to_lowercase();
+        if extension.is_empty() {
+            return;
+        }
+        self.by_extension.insert(extension, behavior);
+    }
+
+    /// Returns the view preferences to use for a file with the given extension
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The extension of the file, if present
+    pub fn view_preferences_for(&self, extension: Option<&str>) -> ViewPreferences {
+        extension
+            .and_then(|e| self.view_preferences.get(&e.to_lowercase()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns true if view preferences are remembered for the given extension
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The extension of the file
+    pub fn remembers_view(&self, extension: &str) -> bool {
+        self.view_preferences
+            .contains_key(&extension.to_lowercase())
+    }
+
+    /// Remembers the behavior and view preferences for all files with the given extension
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The extension to remember the view for
+    /// * `behavior` - The behavior to open the files with
+    /// * `preferences` - The view preferences to present the files with
+    pub fn remember_view(
+        &mut self,
+        extension: &str,
+        behavior: OpenBehavior,
+        preferences: ViewPreferences,
+    ) {
+        self.set(extension, behavior);
+        self.view_preferences
+            .insert(extension.to_lowercase(), preferences);
+    }
+
+    /// Forgets the view preferences for the given extension. The behavior is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The extension to forget the view for
+    pub fn forget_view(&mut self, extension: &str) {
+        self.view_preferences.remove(&extension.to_lowercase());
+    }
+
+    /// Removes the association for the given extension
+    ///
+    /// # Arguments
+    ///
+    /// * `extension` - The extension to remove
+    pub fn remove(&mut self, extension: &str) {
+        self.by_extension.remove(extension);
+    }
+}
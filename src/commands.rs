@@ -0,0 +1,244 @@
+use std::path::Path;
+
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+
+use crate::app::{Action, FileExplorerApp, KindFilter, SearchMode};
+#[cfg(feature = "serde")]
+use crate::export::ExportTarget;
+use crate::fs_utils::FileSource;
+
+/// An entry of the command palette
+#[derive(Debug, Clone)]
+pub struct Command {
+    /// The human-readable name of the command
+    pub label: &'static str,
+    /// The action dispatched when the command is run
+    pub action: Action,
+}
+
+/// Returns the commands that can currently be run from the command palette
+///
+/// # Arguments
+///
+/// * `app` - The application instance
+pub fn available_commands(app: &FileExplorerApp) -> Vec<Command> {
+    let mut commands = vec![
+        Command {
+            label: "Go Up One Directory",
+            action: Action::GoBack(),
+        },
+        Command {
+            label: "Refresh",
+            action: Action::Refresh,
+        },
+        Command {
+            label: "Open Settings",
+            action: Action::OpenSettingsModal,
+        },
+        Command {
+            label: "Edit File Associations",
+            action: Action::OpenAssociationsModal,
+        },
+        Command {
+            label: "Show Folders and Files",
+            action: Action::SetKindFilter(KindFilter::All),
+        },
+        Command {
+            label: "Show Folders Only",
+            action: Action::SetKindFilter(KindFilter::FoldersOnly),
+        },
+        Command {
+            label: "Show Files Only",
+            action: Action::SetKindFilter(KindFilter::FilesOnly),
+        },
+        Command {
+            label: if app.filters.show_hidden {
+                "Hide Hidden Files"
+            } else {
+                "Show Hidden Files"
+            },
+            action: Action::SetShowHidden(!app.filters.show_hidden),
+        },
+        Command {
+            label: "Use Substring Search",
+            action: Action::SetSearchMode(SearchMode::Substring),
+        },
+        Command {
+            label: "Use Fuzzy Search",
+            action: Action::SetSearchMode(SearchMode::Fuzzy),
+        },
+        Command {
+            label: "Use Regex Search",
+            action: Action::SetSearchMode(SearchMode::Regex),
+        },
+        Command {
+            label: if app.sidebar_visible {
+                "Hide File Tree"
+            } else {
+                "Show File Tree"
+            },
+            action: Action::ToggleSidebar,
+        },
+        Command {
+            label: if app.log_panel_open {
+                "Hide Log"
+            } else {
+                "Show Log"
+            },
+            action: Action::ToggleLogPanel,
+        },
+        Command {
+            label: "Clear Log",
+            action: Action::ClearLog,
+        },
+        Command {
+            label: if app.show_whitespace {
+                "Hide Whitespace"
+            } else {
+                "Show Whitespace"
+            },
+            action: Action::SetShowWhitespace(!app.show_whitespace),
+        },
+        Command {
+            label: if app.show_line_numbers {
+                "Hide Line Numbers"
+            } else {
+                "Show Line Numbers"
+            },
+            action: Action::SetShowLineNumbers(!app.show_line_numbers),
+        },
+        Command {
+            label: "Compare With Folder...",
+            action: Action::CompareWithFolder,
+        },
+        Command {
+            label: "New Folder...",
+            action: Action::OpenNewFolder,
+        },
+        Command {
+            label: if app.selection.is_empty() {
+                "Batch Rename Shown Files..."
+            } else {
+                "Batch Rename Selected Files..."
+            },
+            action: Action::OpenBatchRename,
+        },
+        Command {
+            label: "Next Page",
+            action: Action::NextPage,
+        },
+        Command {
+            label: "Previous Page",
+            action: Action::PreviousPage,
+        },
+    ];
+
+    #[cfg(feature = "serde")]
+    commands.extend([
+        Command {
+            label: "Copy Listing as JSON",
+            action: Action::ExportListing(ExportTarget::Clipboard, false),
+        },
+        Command {
+            label: "Export Listing as JSON...",
+            action: Action::ExportListing(ExportTarget::File, false),
+        },
+        Command {
+            label: "Export Listing With Subfolders as JSON...",
+            action: Action::ExportListing(ExportTarget::File, true),
+        },
+    ]);
+
+    if app.clipboard.is_some() {
+        commands.push(Command {
+            label: "Paste",
+            action: Action::Paste,
+        });
+    }
+
+    // Directories within archives can't be bookmarked
+    if app.opened_dir.source == FileSource::Disk {
+        let path = app.opened_dir.absolute_path.clone();
+        commands.push(if app.bookmarks.contains(&path) {
+            Command {
+                label: "Remove Bookmark",
+                action: Action::RemoveBookmark(path),
+            }
+        } else {
+            Command {
+                label: "Bookmark Folder",
+                action: Action::AddBookmark(path),
+            }
+        });
+    }
+
+    commands.push(Command {
+        label: "New Tab",
+        action: Action::NewTab,
+    });
+    // The last tab stays open
+    if app.tabs.len() > 1 {
+        commands.push(Command {
+            label: "Close Tab",
+            action: Action::CloseActiveTab,
+        });
+    }
+
+    if !app.undo_stack.is_empty() {
+        commands.push(Command {
+            label: "Undo",
+            action: Action::Undo,
+        });
+    }
+
+    // Commands that need an opened file
+    if let Some(file) = &app.opened_file {
+        commands.push(Command {
+            label: "Copy File Name",
+            action: Action::CopyToClipboard(file.file_name.clone()),
+        });
+        if let Some(stem) = Path::new(&file.file_name).file_stem() {
+            commands.push(Command {
+                label: "Copy File Name Without Extension",
+                action: Action::CopyToClipboard(stem.to_string_lossy().to_string()),
+            });
+        }
+        commands.push(Command {
+            label: "Close File",
+            action: Action::CloseFile,
+        });
+        commands.push(Command {
+            label: "Save File As...",
+            action: Action::SaveAs,
+        });
+    }
+
+    commands
+}
+
+/// Returns the available commands matching the query, best matches first
+///
+/// # Arguments
+///
+/// * `app` - The application instance
+/// * `query` - The text typed into the command palette
+pub fn filter_commands(app: &FileExplorerApp, query: &str) -> Vec<Command> {
+    let commands = available_commands(app);
+    let query = query.trim();
+    if query.is_empty() {
+        return commands;
+    }
+
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<(i64, Command)> = commands
+        .into_iter()
+        .filter_map(|command| {
+            matcher
+                .fuzzy_match(command.label, query)
+                .map(|score| (score, command))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    scored.into_iter().map(|(_, command)| command).collect()
+}
@@ -0,0 +1,225 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{self, File},
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::fs_utils::DirWalker;
+
+/// The number of bytes compared at a time when comparing file contents
+const COMPARE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// How an entry differs between the compared directories
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difference {
+    /// The entry only exists in the left directory
+    OnlyInLeft,
+    /// The entry only exists in the right directory
+    OnlyInRight,
+    /// The entry exists in both directories, but differs
+    Differs,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Difference::OnlyInLeft => "Only in left",
+            Difference::OnlyInRight => "Only in right",
+            Difference::Differs => "Differs",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// An entry that differs between the compared directories
+#[derive(Debug, Clone)]
+pub struct ComparedEntry {
+    /// The path of the entry relative to the compared directories
+    pub relative_path: PathBuf,
+    /// A flag to indicate if this entry is a directory (on either side)
+    pub is_dir: bool,
+    /// How the entry differs
+    pub difference: Difference,
+}
+
+/// The result of comparing two directories
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    /// The left directory
+    pub left: PathBuf,
+    /// The right directory
+    pub right: PathBuf,
+    /// A flag to indicate if the contents of files were compared, instead of
+    /// only their size and modification time
+    pub compared_contents: bool,
+    /// The entries that differ, ordered by path
+    pub entries: Vec<ComparedEntry>,
+    /// A flag set when either walk stopped at the maximum depth (in which case
+    /// the comparison is incomplete)
+    pub depth_limit_reached: bool,
+}
+
+/// What is known about an entry of a walked directory
+struct EntryInfo {
+    is_dir: bool,
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Compares two directory trees. Entries only on one side are reported once,
+/// without their descendants. Files on both sides differ when their size differs,
+/// then either when their contents differ (if `compare_contents` is set) or when
+/// their modification time differs.
+///
+/// # Arguments
+///
+/// * `left` - The left directory
+/// * `right` - The right directory
+/// * `max_depth` - The maximum depth to descend to
+/// * `compare_contents` - A flag to compare the contents of files of the same size
+pub fn compare_dirs(
+    left: &Path,
+    right: &Path,
+    max_depth: usize,
+    compare_contents: bool,
+) -> Comparison {
+    let (left_entries, left_limited) = walk_relative(left, max_depth);
+    let (right_entries, right_limited) = walk_relative(right, max_depth);
+
+    let mut paths: Vec<&PathBuf> = left_entries.keys().chain(right_entries.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    let mut entries: Vec<ComparedEntry> = Vec::new();
+    // The last directory reported as only on one side, whose descendants are skipped
+    let mut skipped_dir: Option<&PathBuf> = None;
+
+    for path in paths {
+        if skipped_dir.is_some_and(|dir| path.starts_with(dir)) {
+            continue;
+        }
+
+        let (is_dir, difference) = match (left_entries.get(path), right_entries.get(path)) {
+            (Some(l), None) => (l.is_dir, Difference::OnlyInLeft),
+            (None, Some(r)) => (r.is_dir, Difference::OnlyInRight),
+            (Some(l), Some(r)) => {
+                let same = match (l.is_dir, r.is_dir) {
+                    (true, true) => true,
+                    (false, false) if l.size != r.size => false,
+                    (false, false) if compare_contents => {
+                        same_contents(&left.join(path), &right.join(path))
+                    }
+                    (false, false) => l.modified == r.modified,
+                    _ => false,
+                };
+                if same {
+                    continue;
+                }
+                (l.is_dir || r.is_dir, Difference::Differs)
+            }
+            (None, None) => continue,
+        };
+
+        if is_dir && difference != Difference::Differs {
+            skipped_dir = Some(path);
+        }
+
+        entries.push(ComparedEntry {
+            relative_path: path.clone(),
+            is_dir,
+            difference,
+        });
+    }
+
+    Comparison {
+        left: left.to_path_buf(),
+        right: right.to_path_buf(),
+        compared_contents: compare_contents,
+        entries,
+        depth_limit_reached: left_limited || right_limited,
+    }
+}
+
+/// Walks a directory, returning its entries by their path relative to `root`
+/// and whether the maximum depth was reached
+///
+/// # Arguments
+///
+/// * `root` - The directory to walk
+/// * `max_depth` - The maximum depth to descend to
+fn walk_relative(root: &Path, max_depth: usize) -> (BTreeMap<PathBuf, EntryInfo>, bool) {
+    let mut walker = DirWalker::new(root, max_depth);
+    let mut entries = BTreeMap::new();
+
+    for entry in walker.by_ref() {
+        let Ok(relative_path) = entry.path.strip_prefix(root) else {
+            continue;
+        };
+        let metadata = fs::symlink_metadata(&entry.path).ok();
+
+        entries.insert(
+            relative_path.to_path_buf(),
+            EntryInfo {
+                is_dir: entry.is_dir,
+                size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+                modified: metadata.and_then(|m| m.modified().ok()),
+            },
+        );
+    }
+
+    (entries, walker.depth_limit_reached)
+}
+
+/// Returns true if both files have the same contents. Files that can't be read
+/// are considered different.
+///
+/// # Arguments
+///
+/// * `left` - The first file
+/// * `right` - The second file
+fn same_contents(left: &Path, right: &Path) -> bool {
+    let (Ok(left), Ok(right)) = (File::open(left), File::open(right)) else {
+        return false;
+    };
+    let mut left = BufReader::new(left);
+    let mut right = BufReader::new(right);
+    let mut left_buffer = vec![0; COMPARE_CHUNK_SIZE];
+    let mut right_buffer = vec![0; COMPARE_CHUNK_SIZE];
+
+    loop {
+        let (Ok(left_read), Ok(right_read)) = (
+            read_chunk(&mut left, &mut left_buffer),
+            read_chunk(&mut right, &mut right_buffer),
+        ) else {
+            return false;
+        };
+
+        if left_buffer[..left_read] != right_buffer[..right_read] {
+            return false;
+        }
+        if left_read == 0 {
+            return true;
+        }
+    }
+}
+
+/// Fills as much of the buffer as possible, returning the number of bytes read
+/// (which is less than the buffer's length only at the end of the file)
+///
+/// # Arguments
+///
+/// * `reader` - The reader to read from
+/// * `buffer` - The buffer to fill
+fn read_chunk(reader: &mut impl Read, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..])? {
+            0 => break,
+            read => filled += read,
+        }
+    }
+    Ok(filled)
+}
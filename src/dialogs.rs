@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use rfd::AsyncFileDialog;
+
+/// Opens a native dialog to pick a folder. Returns `None` if the dialog was cancelled.
+///
+/// # Arguments
+///
+/// * `start_dir` - The directory the dialog starts in
+pub async fn pick_folder(start_dir: String) -> Option<PathBuf> {
+    AsyncFileDialog::new()
+        .set_directory(start_dir)
+        .pick_folder()
+        .await
+        .map(|handle| handle.path().to_path_buf())
+}
+
+/// Opens a native save dialog. Returns `None` if the dialog was cancelled.
+///
+/// # Arguments
+///
+/// * `start_dir` - The directory the dialog starts in
+/// * `file_name` - The file name the dialog is prefilled with
+pub async fn save_file(start_dir: String, file_name: String) -> Option<PathBuf> {
+    AsyncFileDialog::new()
+        .set_directory(start_dir)
+        .set_file_name(file_name)
+        .save_file()
+        .await
+        .map(|handle| handle.path().to_path_buf())
+}
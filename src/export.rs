@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use crate::fs_utils::{DirWalker, FileNode};
+
+/// Where an exported directory listing goes
+#[cfg(feature = "iced-ui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTarget {
+    /// The system clipboard
+    Clipboard,
+    /// A file picked with the save dialog
+    File,
+}
+
+/// Serializes file nodes to a pretty-printed JSON array
+///
+/// # Arguments
+///
+/// * `nodes` - The file nodes to serialize
+pub fn listing_to_json<'a>(
+    nodes: impl IntoIterator<Item = &'a FileNode>,
+) -> Result<String, String> {
+    let nodes: Vec<&FileNode> = nodes.into_iter().collect();
+    serde_json::to_string_pretty(&nodes).map_err(|e| e.to_string())
+}
+
+/// Walks a directory and returns the file nodes of all of its descendants.
+/// Entries whose metadata can't be read are left out.
+///
+/// # Arguments
+///
+/// * `root` - The directory to walk
+/// * `max_depth` - The maximum depth to descend to
+/// * `follow_symlinks` - A flag to descend into symlinked directories
+/// * `resolve_symlinks` - A flag to resolve the symbolic links within the paths
+pub fn walk_listing(
+    root: &Path,
+    max_depth: usize,
+    follow_symlinks: bool,
+    resolve_symlinks: bool,
+) -> Vec<FileNode> {
+    DirWalker::new(root, max_depth)
+        .follow_symlinks(follow_symlinks)
+        .filter_map(|entry| {
+            FileNode::from_path(&entry.path.to_string_lossy(), resolve_symlinks)
+                .inspect_err(|e| log::warn!("Could not export {}: {}", entry.path.display(), e))
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs_utils::DEFAULT_MAX_WALK_DEPTH;
+    use crate::test_utils::{file_node, temp_dir};
+    use std::fs;
+
+    #[test]
+    fn listing_to_json_writes_the_metadata_fields() {
+        let mut node = file_node("/project/notes.txt", false);
+        node.size_bytes = 1536;
+        node.file_size = String::from("1.5 KiB");
+        node.modified_at = String::from("2024-01-02 03:04:05");
+
+        let json = listing_to_json([&node]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!([{
+                "name": "notes.txt",
+                "path": "/project/notes.txt",
+                "is_dir": false,
+                "size_bytes": 1536,
+                "size": "1.5 KiB",
+                "modified": "2024-01-02 03:04:05",
+            }])
+        );
+    }
+
+    #[test]
+    fn walk_listing_lists_every_descendant() {
+        let dir = temp_dir("walk-listing");
+        fs::write(dir.join("a.txt"), "a").unwrap();
+        fs::create_dir(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub").join("b.txt"), "b").unwrap();
+
+        let mut paths: Vec<String> = walk_listing(&dir, DEFAULT_MAX_WALK_DEPTH, false, false)
+            .into_iter()
+            .map(|node| node.absolute_path)
+            .collect();
+        paths.sort();
+
+        let expected = [
+            dir.join("a.txt"),
+            dir.join("sub"),
+            dir.join("sub").join("b.txt"),
+        ];
+        assert_eq!(
+            paths,
+            expected
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn walk_listing_stops_at_the_maximum_depth() {
+        let dir = temp_dir("walk-listing-depth");
+        fs::create_dir(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub").join("b.txt"), "b").unwrap();
+
+        let names: Vec<String> = walk_listing(&dir, 1, false, false)
+            .into_iter()
+            .map(|node| node.file_name)
+            .collect();
+
+        assert_eq!(names, vec!["sub"]);
+    }
+}
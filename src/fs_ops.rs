@@ -0,0 +1,604 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "iced-ui")]
+use iced::futures::{SinkExt, Stream, channel::mpsc};
+#[cfg(feature = "iced-ui")]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// The number of bytes copied between progress updates
+#[cfg(feature = "iced-ui")]
+const COPY_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The events emitted while a file is being copied
+#[cfg(feature = "iced-ui")]
+#[derive(Debug, Clone)]
+pub enum CopyEvent {
+    /// The number of bytes copied so far and the total size of the file
+    Progress { copied: u64, total: u64 },
+    /// The copy completed with the destination path, or failed with an error
+    Finished(Result<PathBuf, String>),
+}
+
+/// Returns a path in `dir` for `file_name` that does not exist yet. If the
+/// name is taken, a numbered suffix is appended (e.g. `notes (1).txt`).
+///
+/// # Arguments
+///
+/// * `dir` - The directory the file will be placed in
+/// * `file_name` - The preferred name of the file
+pub fn unique_destination(dir: &Path, file_name: &str) -> PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file_name);
+    let extension = path.extension().and_then(|e| e.to_str());
+
+    let mut suffix = 1;
+    loop {
+        let name = match extension {
+            Some(ext) => format!("{} ({}).{}", stem, suffix, ext),
+            None => format!("{} ({})", stem, suffix),
+        };
+        let candidate = dir.join(name);
+        if !candidate.exists() {
+            return candidate;
+        }
+        suffix += 1;
+    }
+}
+
+/// Copies a file in chunks on the async runtime, reporting the progress as it goes.
+/// The stream ends with a [`CopyEvent::Finished`] event.
+///
+/// # Arguments
+///
+/// * `source` - The file to copy
+/// * `destination` - The path to copy the file to. Must not exist yet
+#[cfg(feature = "iced-ui")]
+pub fn copy_file_with_progress(
+    source: PathBuf,
+    destination: PathBuf,
+) -> impl Stream<Item = CopyEvent> {
+    iced::stream::channel(16, move |mut sender: mpsc::Sender<CopyEvent>| async move {
+        let result = copy_chunks(&source, &destination, &mut sender)
+            .await
+            .map(|_| destination)
+            .map_err(|e| e.to_string());
+        let _ = sender.send(CopyEvent::Finished(result)).await;
+    })
+}
+
+#[cfg(feature = "iced-ui")]
+async fn copy_chunks(
+    source: &Path,
+    destination: &Path,
+    sender: &mut mpsc::Sender<CopyEvent>,
+) -> Result<(), std::io::Error> {
+    let mut reader = tokio::fs::File::open(source).await?;
+    let total = reader.metadata().await?.len();
+    let mut writer = tokio::fs::File::create_new(destination).await?;
+
+    let mut buffer = vec![0u8; COPY_CHUNK_SIZE];
+    let mut copied = 0;
+    loop {
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..read]).await?;
+        copied += read as u64;
+        let _ = sender.send(CopyEvent::Progress { copied, total }).await;
+    }
+
+    writer.flush().await
+}
+
+/// Saves a copy of a file to a new location. If `contents` is given it is
+/// written as the new file (as UTF-8), otherwise the original bytes of `source`
+/// are copied, keeping their encoding.
+///
+/// # Arguments
+///
+/// * `source` - The file being saved
+/// * `destination` - The path to save the copy to
+/// * `contents` - The text to write instead of the original bytes (if present)
+pub async fn save_copy(
+    source: PathBuf,
+    destination: PathBuf,
+    contents: Option<String>,
+) -> Result<PathBuf, String> {
+    let result = match contents {
+        Some(contents) => tokio::fs::write(&destination, contents).await,
+        None => tokio::fs::copy(&source, &destination).await.map(|_| ()),
+    };
+
+    result.map(|_| destination).map_err(|e| e.to_string())
+}
+
+/// The placeholders of a batch rename pattern
+pub const RENAME_PLACEHOLDERS: &str = "{n} index, {name} name, {ext} extension";
+
+/// A file rename planned by a batch rename
+#[derive(Debug, Clone)]
+pub struct PlannedRename {
+    /// The file to rename
+    pub source: PathBuf,
+    /// The path the file is renamed to
+    pub destination: PathBuf,
+    /// Why the file can't be renamed (if it can't)
+    pub problem: Option<&'static str>,
+}
+
+/// Plans renaming files following a pattern, then replacing `find` with
+/// `replace` in the resulting names. Files whose name doesn't change are left out.
+///
+/// # Arguments
+///
+/// * `sources` - The files to rename, in the order used for `{n}`
+/// * `pattern` - The new name, with the `{n}` (index from 1), `{name}` (the name
+///   without its extension) and `{ext}` (the extension) placeholders
+/// * `find` - The text to replace in the new names (ignored if empty)
+/// * `replace` - The text replacing `find`
+pub fn plan_batch_rename(
+    sources: &[PathBuf],
+    pattern: &str,
+    find: &str,
+    replace: &str,
+) -> Vec<PlannedRename> {
+    let mut plan: Vec<PlannedRename> = Vec::new();
+    let mut destinations: HashSet<PathBuf> = HashSet::new();
+
+    for (index, source) in sources.iter().enumerate() {
+        let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let extension = source.extension().and_then(|e| e.to_str());
+
+        let mut name = match extension {
+            Some(extension) => pattern.replace("{ext}", extension),
+            // Don't leave a trailing dot on files without an extension
+            None => pattern.replace(".{ext}", "").replace("{ext}", ""),
+        };
+        name = name
+            .replace("{n}", &(index + 1).to_string())
+            .replace("{name}", stem);
+        if !find.is_empty() {
+            name = name.replace(find, replace);
+        }
+
+        let destination = source.with_file_name(&name);
+        if destination == *source {
+            continue;
+        }
+
+        let problem = if check_file_name(&name).is_err() {
+            Some("invalid name")
+        } else if !destinations.insert(destination.clone()) {
+            Some("duplicate name")
+        } else if destination.exists() {
+            Some("already exists")
+        } else {
+            None
+        };
+
+        plan.push(PlannedRename {
+            source: source.clone(),
+            destination,
+            problem,
+        });
+    }
+
+    plan
+}
+
+/// Renames the files of a batch rename plan. Nothing is renamed if any of the
+/// renames has a problem or a destination exists, and the renames done so far
+/// are reverted if one fails midway.
+///
+/// # Arguments
+///
+/// * `plan` - The renames to apply
+///
+/// Returns the renames that were applied, as (source, destination) pairs
+pub fn apply_batch_rename(
+    plan: &[PlannedRename],
+) -> Result<Vec<(PathBuf, PathBuf)>, std::io::Error> {
+    // Check every target again, as files may have been created since the preview
+    if let Some(rename) = plan
+        .iter()
+        .find(|rename| rename.problem.is_some() || rename.destination.exists())
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!(
+                "cannot rename {} to {}",
+                rename.source.display(),
+                rename.destination.display()
+            ),
+        ));
+    }
+
+    let mut applied: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for rename in plan {
+        if let Err(e) = std::fs::rename(&rename.source, &rename.destination) {
+            for (source, destination) in applied.iter().rev() {
+                let _ = std::fs::rename(destination, source);
+            }
+            return Err(e);
+        }
+        applied.push((rename.source.clone(), rename.destination.clone()));
+    }
+
+    Ok(applied)
+}
+
+/// Checks that a name can be given to a file or folder within a directory
+///
+/// # Arguments
+///
+/// * `name` - The name to check
+pub fn check_file_name(name: &str) -> Result<(), std::io::Error> {
+    let problem = if name.trim().is_empty() {
+        "the name is empty"
+    } else if name.contains(['/', '\\']) {
+        "the name cannot contain / or \\"
+    } else if name == "." || name == ".." {
+        "the name is reserved"
+    } else {
+        return Ok(());
+    };
+    Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        problem,
+    ))
+}
+
+/// Creates a folder within a directory
+///
+/// # Arguments
+///
+/// * `dir` - The directory to create the folder in
+/// * `name` - The name of the new folder
+///
+/// Returns the path of the new folder
+pub fn create_folder(dir: &Path, name: &str) -> Result<PathBuf, std::io::Error> {
+    check_file_name(name)?;
+    let path = dir.join(name);
+    std::fs::create_dir(&path)?;
+    Ok(path)
+}
+
+/// Renames a file or folder within its directory
+///
+/// # Arguments
+///
+/// * `source` - The file or folder to rename
+/// * `new_name` - The new name of the file or folder
+///
+/// Returns the new path of the file or folder
+pub fn rename_path(source: &Path, new_name: &str) -> Result<PathBuf, std::io::Error> {
+    check_file_name(new_name)?;
+    let destination = source.with_file_name(new_name);
+    if destination.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", new_name),
+        ));
+    }
+    std::fs::rename(source, &destination)?;
+    Ok(destination)
+}
+
+/// Moves a file or folder into another folder. If the name is taken there, a
+/// numbered suffix is appended rather than overwriting it.
+///
+/// # Arguments
+///
+/// * `source` - The file or folder to move
+/// * `folder` - The folder to move it into
+///
+/// Returns the new path of the file or folder
+pub fn move_path(source: &Path, folder: &Path) -> Result<PathBuf, std::io::Error> {
+    let file_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| std::io::Error::other("The path has no file name"))?;
+    let destination = unique_destination(folder, file_name);
+    std::fs::rename(source, &destination)?;
+    Ok(destination)
+}
+
+/// Permanently deletes a file, or a folder with all of its contents. Symbolic
+/// links are removed themselves, never what they point to.
+///
+/// # Arguments
+///
+/// * `path` - The file or folder to delete
+pub fn delete_path(path: &Path) -> Result<(), std::io::Error> {
+    let file_type = std::fs::symlink_metadata(path)?.file_type();
+    if file_type.is_symlink() {
+        // Links to folders are removed like folders on Windows
+        std::fs::remove_file(path).or_else(|_| std::fs::remove_dir(path))
+    } else if file_type.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// A filesystem operation that can be reversed. Operations that can't be
+/// undone (like permanently deleting a file) are never recorded.
+#[derive(Debug, Clone)]
+pub enum UndoableOp {
+    /// A file was copied to `destination`. Undone by removing the copy
+    Copy { destination: PathBuf },
+    /// Files were renamed, as (source, destination) pairs. Undone by renaming them back
+    BatchRename { renames: Vec<(PathBuf, PathBuf)> },
+    /// A file was renamed from `source` to `destination`. Undone by renaming it back
+    Rename {
+        source: PathBuf,
+        destination: PathBuf,
+    },
+    /// A folder was created at `path`. Undone by removing it, if it is still empty
+    CreateFolder { path: PathBuf },
+    /// Files or folders were moved, as (source, destination) pairs. Undone by moving them back
+    Move { moves: Vec<(PathBuf, PathBuf)> },
+}
+
+/// Undoable Operation methods
+impl UndoableOp {
+    /// Reverses the operation
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The operation to reverse
+    ///
+    /// Returns a description of what was undone
+    pub fn undo(&self) -> Result<String, std::io::Error> {
+        match self {
+            UndoableOp::Copy { destination } => {
+                std::fs::remove_file(destination)?;
+                Ok(format!("Removed the copy {}", destination.display()))
+            }
+            UndoableOp::BatchRename { renames } => {
+                // Check every source first so nothing is renamed back halfway
+                if let Some((source, _)) = renames.iter().find(|(source, _)| source.exists()) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::AlreadyExists,
+                        format!("{} already exists", source.display()),
+                    ));
+                }
+                let mut restored: Vec<&(PathBuf, PathBuf)> = Vec::new();
+                for rename in renames.iter().rev() {
+                    let (source, destination) = rename;
+                    if let Err(e) = std::fs::rename(destination, source) {
+                        // Give the files restored so far their new names again
+                        for (source, destination) in restored.iter().rev() {
+                            let _ = std::fs::rename(source, destination);
+                        }
+                        return Err(e);
+                    }
+                    restored.push(rename);
+                }
+                Ok(format!("Restored the names of {} files", renames.len()))
+            }
+            UndoableOp::Rename {
+                source,
+                destination,
+            } => {
+                // Renaming overwrites on some platforms, so never rename over a new file
+                if source.exists() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::AlreadyExists,
+                        format!("{} already exists", source.display()),
+                    ));
+                }
+                std::fs::rename(destination, source)?;
+                Ok(format!("Renamed {} back", destination.display()))
+            }
+            UndoableOp::CreateFolder { path } => {
+                std::fs::remove_dir(path)?;
+                Ok(format!("Removed the folder {}", path.display()))
+            }
+            UndoableOp::Move { moves } => {
+                // Check every source first so nothing is moved back halfway
+                if let Some((source, _)) = moves.iter().find(|(source, _)| source.exists()) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::AlreadyExists,
+                        format!("{} already exists", source.display()),
+                    ));
+                }
+                for (source, destination) in moves.iter().rev() {
+                    std::fs::rename(destination, source)?;
+                }
+                match moves.as_slice() {
+                    [(source, _)] => Ok(format!("Moved {} back", source.display())),
+                    _ => Ok(format!("Moved {} files back", moves.len())),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn undo_copy_removes_the_copy() {
+        let dir = temp_dir("undo-copy");
+        let source = dir.join("notes.txt");
+        let destination = dir.join("notes (1).txt");
+        fs::write(&source, "notes").unwrap();
+        fs::copy(&source, &destination).unwrap();
+
+        UndoableOp::Copy {
+            destination: destination.clone(),
+        }
+        .undo()
+        .unwrap();
+
+        assert!(source.exists());
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn undo_rename_restores_the_name() {
+        let dir = temp_dir("undo-rename");
+        let source = dir.join("old.txt");
+        fs::write(&source, "contents").unwrap();
+        let destination = rename_path(&source, "new.txt").unwrap();
+
+        UndoableOp::Rename {
+            source: source.clone(),
+            destination: destination.clone(),
+        }
+        .undo()
+        .unwrap();
+
+        assert_eq!(fs::read_to_string(&source).unwrap(), "contents");
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn undo_rename_keeps_a_file_that_took_the_old_name() {
+        let dir = temp_dir("undo-rename-taken");
+        let source = dir.join("old.txt");
+        fs::write(&source, "renamed").unwrap();
+        let destination = rename_path(&source, "new.txt").unwrap();
+        fs::write(&source, "new").unwrap();
+
+        let undone = UndoableOp::Rename {
+            source: source.clone(),
+            destination: destination.clone(),
+        }
+        .undo();
+
+        assert!(undone.is_err());
+        assert_eq!(fs::read_to_string(&source).unwrap(), "new");
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "renamed");
+    }
+
+    #[test]
+    fn undo_batch_rename_restores_every_name() {
+        let dir = temp_dir("undo-batch-rename");
+        let sources = vec![dir.join("a.txt"), dir.join("b.txt")];
+        for source in &sources {
+            fs::write(source, "").unwrap();
+        }
+        let plan = plan_batch_rename(&sources, "file {n}.{ext}", "", "");
+        let renames = apply_batch_rename(&plan).unwrap();
+
+        UndoableOp::BatchRename { renames }.undo().unwrap();
+
+        assert!(sources.iter().all(|source| source.exists()));
+        assert!(!dir.join("file 1.txt").exists());
+        assert!(!dir.join("file 2.txt").exists());
+    }
+
+    #[test]
+    fn undo_batch_rename_keeps_a_file_that_took_an_old_name() {
+        let dir = temp_dir("undo-batch-rename-taken");
+        let sources = vec![dir.join("a.txt"), dir.join("b.txt")];
+        for source in &sources {
+            fs::write(source, "renamed").unwrap();
+        }
+        let plan = plan_batch_rename(&sources, "file {n}.{ext}", "", "");
+        let renames = apply_batch_rename(&plan).unwrap();
+        fs::write(&sources[1], "new").unwrap();
+
+        let undone = UndoableOp::BatchRename { renames }.undo();
+
+        // Nothing is renamed back, not even the file whose old name is free
+        assert!(undone.is_err());
+        assert!(!sources[0].exists());
+        assert_eq!(fs::read_to_string(&sources[1]).unwrap(), "new");
+        assert!(dir.join("file 1.txt").exists());
+        assert!(dir.join("file 2.txt").exists());
+    }
+
+    #[test]
+    fn plan_batch_rename_rejects_reserved_names() {
+        let dir = temp_dir("plan-batch-rename-reserved");
+        let sources = vec![dir.join("a.txt"), dir.join("b.txt")];
+
+        let plan = plan_batch_rename(&sources, "..", "", "");
+
+        assert!(
+            plan.iter()
+                .all(|rename| rename.problem == Some("invalid name"))
+        );
+    }
+
+    #[test]
+    fn undo_create_folder_removes_the_folder() {
+        let dir = temp_dir("undo-create-folder");
+        let path = create_folder(&dir, "new folder").unwrap();
+
+        UndoableOp::CreateFolder { path: path.clone() }
+            .undo()
+            .unwrap();
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn undo_create_folder_keeps_a_folder_with_contents() {
+        let dir = temp_dir("undo-create-folder-contents");
+        let path = create_folder(&dir, "new folder").unwrap();
+        fs::write(path.join("notes.txt"), "notes").unwrap();
+
+        let undone = UndoableOp::CreateFolder { path: path.clone() }.undo();
+
+        assert!(undone.is_err());
+        assert!(path.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn undo_move_moves_back() {
+        let dir = temp_dir("undo-move");
+        let folder = dir.join("folder");
+        fs::create_dir(&folder).unwrap();
+        let source = dir.join("notes.txt");
+        fs::write(&source, "notes").unwrap();
+        let destination = move_path(&source, &folder).unwrap();
+
+        UndoableOp::Move {
+            moves: vec![(source.clone(), destination.clone())],
+        }
+        .undo()
+        .unwrap();
+
+        assert!(source.exists());
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn undo_move_keeps_a_file_that_took_the_old_path() {
+        let dir = temp_dir("undo-move-taken");
+        let folder = dir.join("folder");
+        fs::create_dir(&folder).unwrap();
+        let source = dir.join("notes.txt");
+        fs::write(&source, "moved").unwrap();
+        let destination = move_path(&source, &folder).unwrap();
+        fs::write(&source, "new").unwrap();
+
+        let undone = UndoableOp::Move {
+            moves: vec![(source.clone(), destination.clone())],
+        }
+        .undo();
+
+        assert!(undone.is_err());
+        assert_eq!(fs::read_to_string(&source).unwrap(), "new");
+        assert_eq!(fs::read_to_string(&destination).unwrap(), "moved");
+    }
+}
@@ -1,36 +1,206 @@
 use std::{
-    fs::{self, canonicalize},
-    path::Path,
+    collections::HashSet,
+    fs::{self, DirEntry, canonicalize},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::SystemTime,
+};
+#[cfg(feature = "iced-ui")]
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Component, MAIN_SEPARATOR},
+    process::Command,
 };
 
+#[cfg(feature = "iced-ui")]
+use base64::{Engine, engine::general_purpose::STANDARD};
+#[cfg(feature = "iced-ui")]
+use chardetng::EncodingDetector;
 use chrono::DateTime;
 use chrono::offset::Local;
-use humansize::{DECIMAL, format_size};
+#[cfg(feature = "iced-ui")]
+use encoding_rs::Encoding;
+use humansize::{BINARY, FormatSizeOptions, format_size};
+#[cfg(feature = "iced-ui")]
+use sysinfo::Disks;
+
+use crate::archive::{read_archive_dir, read_archive_entry};
+use crate::git::{GitStatus, annotate_git_status};
 
 const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
-/// Represents a node in the file menu
+/// The format of the dates shown instead of relative times for older files
+#[cfg(feature = "iced-ui")]
+const DAY_FORMAT: &str = "%Y-%m-%d";
+
+/// How sizes are formatted everywhere in the application: binary units with at
+/// most one decimal (such as "1.4 KiB")
+const SIZE_FORMAT: FormatSizeOptions = FormatSizeOptions {
+    decimal_places: 1,
+    ..BINARY
+};
+
+/// Formats a number of bytes as a human-readable size in binary units (such as
+/// "1.4 KiB")
+///
+/// # Arguments
+///
+/// * `bytes` - The number of bytes
+pub fn human_size(bytes: u64) -> String {
+    format_size(bytes, SIZE_FORMAT)
+}
+
+/// Represents a node in the file menu. With the `serde` feature, it serializes
+/// to its name, path, kind, size and modification time.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FileNode {
     /// The name of the file (excluding the path)
+    #[cfg_attr(feature = "serde", serde(rename = "name"))]
     pub file_name: String,
     /// The absolute path to this file, including the file name
+    #[cfg_attr(feature = "serde", serde(rename = "path"))]
     pub absolute_path: String,
     /// The parent folder of this file (Empty for the root folder)
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub parent_folder: Option<String>,
     /// A flag to indicate if this node is a directory
     pub is_dir: bool,
     /// A flag to indicate if this FileNode should be rendered
     /// as it matches the file filters
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub matches_filters: bool,
+    /// The score of the fuzzy search match (if fuzzy search is active)
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub match_score: Option<i64>,
+    /// The indices of the characters in `file_name` matched by the search
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub match_indices: Vec<usize>,
+    /// The size of the file in bytes (meaningless for directories)
+    pub size_bytes: u64,
     // the size of the file as a human-readable string
+    #[cfg_attr(feature = "serde", serde(rename = "size"))]
     pub file_size: String,
     // When the file was created
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub created_at: String,
     // When the file was last modified
+    #[cfg_attr(feature = "serde", serde(rename = "modified"))]
     pub modified_at: String,
+    /// When the file was last modified, kept to show how long ago that was
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub modified: Option<SystemTime>,
     // When the file was last accessed
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub accessed_at: String,
+    /// The git status of the file, if it is within a repository and has changes
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub git_status: Option<GitStatus>,
+    /// Where the contents of this file are read from
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub source: FileSource,
+    /// A flag to indicate if this node is a symbolic link
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub is_symlink: bool,
+    /// The path a symbolic link points to, as written in the link
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub link_target: Option<String>,
+    /// The children of this directory, read the first time it is expanded in
+    /// the file tree
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub children: Option<Vec<FileNode>>,
+    /// A flag to indicate if the children of this directory are shown below it
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub expanded: bool,
+    /// How many expanded directories this node is nested in within the file tree
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub depth: usize,
+}
+
+/// Where the contents of a file node are read from
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum FileSource {
+    /// A file or directory on disk
+    #[default]
+    Disk,
+    /// An entry within an archive, which is read-only
+    Archive {
+        /// The path of the archive on disk
+        archive_path: String,
+        /// The path of the entry within the archive (directories end with `/`)
+        entry_path: String,
+    },
+}
+
+/// How file extensions are shown in the file tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtensionDisplay {
+    /// As part of the file name
+    #[default]
+    Inline,
+    /// Not at all, only the file stem is shown
+    Hidden,
+    /// In a column of their own, after the file stem
+    Column,
+}
+
+impl ExtensionDisplay {
+    /// All of the ways to show extensions, in the order they are shown to the user
+    pub const ALL: [ExtensionDisplay; 3] = [
+        ExtensionDisplay::Inline,
+        ExtensionDisplay::Hidden,
+        ExtensionDisplay::Column,
+    ];
+}
+
+impl std::fmt::Display for ExtensionDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtensionDisplay::Inline => write!(f, "With the name"),
+            ExtensionDisplay::Hidden => write!(f, "Hidden"),
+            ExtensionDisplay::Column => write!(f, "In a column"),
+        }
+    }
+}
+
+/// The orders the file tree can be sorted in. Directories are listed first in each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SortMode {
+    /// By name, from A to Z
+    #[default]
+    NameAsc,
+    /// By name, from Z to A
+    NameDesc,
+    /// By size, largest first
+    SizeDesc,
+    /// By modification time, newest first
+    ModifiedDesc,
+}
+
+impl SortMode {
+    /// All of the sort modes, in the order they are shown to the user
+    pub const ALL: [SortMode; 4] = [
+        SortMode::NameAsc,
+        SortMode::NameDesc,
+        SortMode::SizeDesc,
+        SortMode::ModifiedDesc,
+    ];
+}
+
+impl std::fmt::Display for SortMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortMode::NameAsc => write!(f, "Name (A to Z)"),
+            SortMode::NameDesc => write!(f, "Name (Z to A)"),
+            SortMode::SizeDesc => write!(f, "Largest first"),
+            SortMode::ModifiedDesc => write!(f, "Newest first"),
+        }
+    }
 }
 
 /// File Node methods
@@ -41,25 +211,87 @@ impl FileNode {
     ///
     /// * `path` - The path to read
     pub fn from_relative_path(path: &String) -> Result<FileNode, std::io::Error> {
+        FileNode::from_path(path, true)
+    }
+
+    /// Constructs a file node from a path. Symbolic links are described by the
+    /// metadata of their target either way, but `resolve_symlinks` decides if the
+    /// node's path is the target's or the link's own.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to read
+    /// * `resolve_symlinks` - A flag to resolve the symbolic links within the path
+    pub fn from_path(path: &str, resolve_symlinks: bool) -> Result<FileNode, std::io::Error> {
         let current_path = Path::new(path);
-        let absolute_path = canonicalize(current_path)?;
+        let absolute_path = if resolve_symlinks {
+            canonicalize(current_path)?
+        } else {
+            std::path::absolute(current_path)?
+        };
         let metadata = fs::metadata(path)?;
+        let is_symlink = fs::symlink_metadata(path)?.file_type().is_symlink();
         let file_name = match current_path.file_name() {
             Some(p) => String::from(p.to_str().unwrap()),
             None => String::from(path),
         };
 
+        let mut file = FileNode::from_metadata(absolute_path, file_name, &metadata, is_symlink)?;
+        if is_symlink {
+            file.link_target = fs::read_link(path)
+                .ok()
+                .map(|target| target.to_string_lossy().into_owned());
+        }
+        Ok(file)
+    }
+
+    /// Constructs a file node from an entry of a directory listing. This reuses
+    /// the entry's file type and metadata, which saves a `stat` per entry compared
+    /// to `from_path`. Symbolic links still need their target read, so they are
+    /// handed to `from_path`, keeping the link's own path: deleting, renaming or
+    /// moving the node must act on the link, never on its target.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The directory entry
+    pub fn from_dir_entry(entry: &DirEntry) -> Result<FileNode, std::io::Error> {
+        let path = entry.path();
+        if entry.file_type()?.is_symlink() {
+            return FileNode::from_path(&path.to_string_lossy(), false);
+        }
+
+        let metadata = entry.metadata()?;
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let absolute_path = if path.is_absolute() {
+            path
+        } else {
+            std::path::absolute(path)?
+        };
+
+        FileNode::from_metadata(absolute_path, file_name, &metadata, false)
+    }
+
+    /// Constructs a file node from metadata that was already read
+    ///
+    /// # Arguments
+    ///
+    /// * `absolute_path` - The absolute path of the file
+    /// * `file_name` - The name of the file
+    /// * `metadata` - The metadata of the file (of the target for symbolic links)
+    /// * `is_symlink` - A flag to indicate if the file is a symbolic link
+    fn from_metadata(
+        absolute_path: PathBuf,
+        file_name: String,
+        metadata: &fs::Metadata,
+        is_symlink: bool,
+    ) -> Result<FileNode, std::io::Error> {
         let parent_folder = absolute_path
             .parent()
             .map(|p| String::from(p.to_str().unwrap()));
 
-        let file_size = format_size(metadata.len(), DECIMAL);
-        let created_system_time = metadata.created()?;
-        let accessed_system_time = metadata.accessed()?;
-        let modified_system_time = metadata.modified()?;
-        let created_at: DateTime<Local> = created_system_time.into();
-        let accessed_at: DateTime<Local> = accessed_system_time.into();
-        let modified_at: DateTime<Local> = modified_system_time.into();
+        let file_size = human_size(metadata.len());
+        // Not every platform (or filesystem) records all of these times
+        let modified = metadata.modified().ok();
 
         Ok(FileNode {
             file_name,
@@ -67,74 +299,363 @@ impl FileNode {
             parent_folder,
             is_dir: metadata.is_dir(),
             matches_filters: true,
+            match_score: None,
+            match_indices: Vec::new(),
+            size_bytes: metadata.len(),
             file_size,
-            created_at: created_at.format(DATE_FORMAT).to_string(),
-            modified_at: modified_at.format(DATE_FORMAT).to_string(),
-            accessed_at: accessed_at.format(DATE_FORMAT).to_string(),
+            created_at: format_time(metadata.created().ok()),
+            modified_at: format_time(modified),
+            modified,
+            accessed_at: format_time(metadata.accessed().ok()),
+            git_status: None,
+            source: FileSource::Disk,
+            is_symlink,
+            link_target: None,
+            children: None,
+            expanded: false,
+            depth: 0,
         })
     }
 
+    /// Returns true for hidden files, whose name starts with a dot (such as `.git`)
+    ///
+    /// # Arguments
+    /// * `self` - The file node instance
+    pub fn is_hidden(&self) -> bool {
+        self.file_name.starts_with('.')
+    }
+
     /// Returns a display-friendly name for the file node
     ///
     /// # Arguments
     /// * `self` - The file node instance
     pub fn display_name(&self) -> String {
+        self.display_name_as(ExtensionDisplay::Inline)
+    }
+
+    /// Returns the size of the file in binary units (such as "1.4 KiB"), or
+    /// "—" for directories as their size would have to be walked
+    ///
+    /// # Arguments
+    /// * `self` - The file node instance
+    pub fn human_readable_size(&self) -> String {
         if self.is_dir {
-            format!("📂 {}/", self.file_name)
-        } else {
-            format!("📄 {}", self.file_name)
+            return String::from("—");
         }
+
+        human_size(self.size_bytes)
+    }
+
+    /// Returns a display-friendly name for the file node, showing its extension
+    /// (if any) as requested. Only the displayed name changes, not the file.
+    ///
+    /// # Arguments
+    /// * `self` - The file node instance
+    /// * `extensions` - How the extension is shown
+    pub fn display_name_as(&self, extensions: ExtensionDisplay) -> String {
+        let name = match extensions {
+            ExtensionDisplay::Inline => &self.file_name,
+            ExtensionDisplay::Hidden | ExtensionDisplay::Column => self.split_extension().0,
+        };
+
+        match (self.is_dir, self.is_symlink) {
+            (true, false) => format!("📂 {}/", name),
+            (true, true) => format!("🔗 {}/", name),
+            (false, false) => format!("📄 {}", name),
+            (false, true) => format!("🔗 {}", name),
+        }
+    }
+
+    /// Splits the file name into its stem and its extension (without the dot).
+    /// Directories and dotfiles such as `.bashrc` have no extension.
+    ///
+    /// # Arguments
+    /// * `self` - The file node instance
+    pub fn split_extension(&self) -> (&str, Option<&str>) {
+        if self.is_dir {
+            return (&self.file_name, None);
+        }
+
+        let path = Path::new(&self.file_name);
+        match (
+            path.file_stem().and_then(|s| s.to_str()),
+            path.extension().and_then(|e| e.to_str()),
+        ) {
+            (Some(stem), Some(extension)) => (stem, Some(extension)),
+            _ => (&self.file_name, None),
+        }
+    }
+}
+
+/// Formats a time as a local date and time, or as an empty string if the time
+/// is unknown
+///
+/// # Arguments
+///
+/// * `time` - The time to format
+fn format_time(time: Option<SystemTime>) -> String {
+    time.map(|time| {
+        let time: DateTime<Local> = time.into();
+        time.format(DATE_FORMAT).to_string()
+    })
+    .unwrap_or_default()
+}
+
+/// Describes how long ago a time was, such as "5m ago" or "yesterday". Times
+/// older than a month are shown as a date instead.
+///
+/// # Arguments
+///
+/// * `time` - The time to describe
+#[cfg(feature = "iced-ui")]
+pub fn relative_time(time: SystemTime) -> String {
+    // Times in the future (from clock skew) are treated as now
+    let seconds = SystemTime::now()
+        .duration_since(time)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+
+    match seconds {
+        0..60 => String::from("just now"),
+        60..3_600 => format!("{}m ago", seconds / 60),
+        3_600..86_400 => format!("{}h ago", seconds / 3_600),
+        86_400..172_800 => String::from("yesterday"),
+        172_800..2_592_000 => format!("{}d ago", seconds / 86_400),
+        _ => {
+            let date: DateTime<Local> = time.into();
+            date.format(DAY_FORMAT).to_string()
+        }
+    }
+}
+
+/// A token to cancel a long-running operation from another thread. The operation
+/// checks it between entries, so it stops promptly once the token is cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+/// Cancel Token methods
+impl CancelToken {
+    /// Cancels the operations holding this token (or a clone of it)
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The token instance
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true once the token was cancelled
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The token instance
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
     }
 }
 
-/// Returns a list of all the FileNodes for the given path
+/// The outcome of an operation that can be cancelled with a [`CancelToken`]
+#[derive(Debug, Clone)]
+pub enum Cancelable<T> {
+    /// The operation ran to completion
+    Completed(T),
+    /// The operation stopped early as its token was cancelled
+    Cancelled,
+}
+
+/// Returns the number of entries in a directory, or `None` if it can't be read
+/// (such as when permission is denied)
+///
+/// # Arguments
+///
+/// * `path` - The path of the directory
+#[cfg(feature = "iced-ui")]
+pub fn count_children(path: &Path) -> Option<usize> {
+    fs::read_dir(path).ok().map(|entries| entries.count())
+}
+
+/// Returns true if a symbolic link points to a folder it is within (or to
+/// itself), so expanding it would nest the same folders forever
+///
+/// # Arguments
+///
+/// * `link` - The path of the symbolic link
+#[cfg(feature = "iced-ui")]
+pub fn is_symlink_cycle(link: &Path) -> bool {
+    let (Ok(target), Some(Ok(parent))) = (canonicalize(link), link.parent().map(canonicalize))
+    else {
+        return false;
+    };
+    parent.starts_with(target)
+}
+
+/// Returns a list of all the FileNodes for the given path. A directory that
+/// can't be read (such as when permission is denied) is an error, not an empty list.
 ///
 /// # Arguments
 ///
 /// * `path` - The path to read
 pub fn read_dir(path: &String) -> Result<Vec<FileNode>, std::io::Error> {
+    match read_dir_cancelable(path, &CancelToken::default())? {
+        Cancelable::Completed(nodes) => Ok(nodes),
+        Cancelable::Cancelled => Ok(Vec::new()),
+    }
+}
+
+/// Returns a list of all the FileNodes for the given path, stopping early if
+/// the token is cancelled
+///
+/// # Arguments
+///
+/// * `path` - The path to read
+/// * `cancel` - The token to stop reading with
+pub fn read_dir_cancelable(
+    path: &String,
+    cancel: &CancelToken,
+) -> Result<Cancelable<Vec<FileNode>>, std::io::Error> {
     let mut nodes: Vec<FileNode> = Vec::new();
 
-    let entries = match fs::read_dir(path) {
-        Ok(v) => v,
-        Err(e) => {
-            eprintln!("error: {}", e);
-            return Ok(nodes);
-        }
-    };
+    // Unreadable directories are errors, so they aren't mistaken for empty ones
+    let entries = fs::read_dir(path).inspect_err(|e| {
+        log::warn!("read_dir: could not read {}: {}", path, e);
+    })?;
 
     for entry_result in entries {
+        if cancel.is_cancelled() {
+            log::debug!("read_dir: cancelled reading {}", path);
+            return Ok(Cancelable::Cancelled);
+        }
+
         let entry = match entry_result {
-            Ok(e) => e.path(),
-            Err(_) => return Ok(nodes),
+            Ok(e) => e,
+            Err(_) => return Ok(Cancelable::Completed(nodes)),
         };
 
-        match FileNode::from_relative_path(&String::from(entry.to_str().unwrap())) {
+        match FileNode::from_dir_entry(&entry) {
             Ok(node) => nodes.push(node),
             Err(e) => {
+                let path = entry.path();
                 if e.kind() == std::io::ErrorKind::PermissionDenied {
                     // Skip files that cannot be accessed due to permission issues
-                    eprintln!("read_dir: permission denied for file: {}", entry.display());
+                    log::warn!("read_dir: permission denied for file: {}", path.display());
                     continue;
                 }
-                eprintln!("read_dir: could not read file: {}, {}", e, entry.display());
+                log::warn!("read_dir: could not read file: {}, {}", e, path.display());
                 continue;
             }
         }
     }
 
-    // Sort directories first, then files, both alphabetically
+    sort_nodes(&mut nodes, SortMode::default());
+
+    annotate_git_status(path, &mut nodes);
+
+    Ok(Cancelable::Completed(nodes))
+}
+
+/// Returns true if the directory has more than `limit` entries. Stops reading
+/// after `limit + 1` entries, so it stays cheap for huge directories.
+///
+/// # Arguments
+///
+/// * `path` - The directory to count the entries of
+/// * `limit` - The number of entries to compare against
+pub fn has_more_entries_than(path: &str, limit: usize) -> bool {
+    fs::read_dir(path)
+        .map(|entries| entries.take(limit.saturating_add(1)).count() > limit)
+        .unwrap_or(false)
+}
+
+/// Sorts directories first, then files, both in the order of the sort mode.
+/// Directories are sorted by name when sorting by size, as their size is unknown.
+/// Names that only differ in case (e.g. `File` and `file`) are ordered by their
+/// bytes, then by their path, so reading a directory again never reorders them.
+///
+/// # Arguments
+///
+/// * `nodes` - The nodes to sort
+/// * `mode` - The order to sort the nodes in
+pub fn sort_nodes(nodes: &mut [FileNode], mode: SortMode) {
+    let by_name = |a: &FileNode, b: &FileNode| {
+        a.file_name
+            .to_lowercase()
+            .cmp(&b.file_name.to_lowercase())
+            .then_with(|| a.file_name.cmp(&b.file_name))
+    };
+
     nodes.sort_by(|a, b| {
-        if a.is_dir && !b.is_dir {
-            std::cmp::Ordering::Less
-        } else if !a.is_dir && b.is_dir {
-            std::cmp::Ordering::Greater
-        } else {
-            a.file_name.to_lowercase().cmp(&b.file_name.to_lowercase())
-        }
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| match mode {
+                SortMode::NameAsc => by_name(a, b),
+                SortMode::NameDesc => by_name(b, a),
+                SortMode::SizeDesc if !a.is_dir => {
+                    b.size_bytes.cmp(&a.size_bytes).then_with(|| by_name(a, b))
+                }
+                SortMode::SizeDesc => by_name(a, b),
+                SortMode::ModifiedDesc => b.modified.cmp(&a.modified).then_with(|| by_name(a, b)),
+            })
+            .then_with(|| a.absolute_path.cmp(&b.absolute_path))
     });
+}
+
+/// Returns a list of all the FileNodes within a directory node, whether it is on
+/// disk or within an archive
+///
+/// # Arguments
+///
+/// * `dir` - The directory to read
+pub fn list_dir(dir: &FileNode) -> Result<Vec<FileNode>, std::io::Error> {
+    match &dir.source {
+        FileSource::Disk => read_dir(&dir.absolute_path),
+        FileSource::Archive {
+            archive_path,
+            entry_path,
+        } => read_archive_dir(archive_path, entry_path),
+    }
+}
+
+/// Reads up to `limit` bytes of a file node, whether it is on disk or within an archive
+///
+/// # Arguments
+///
+/// * `file` - The file to read
+/// * `limit` - The maximum number of bytes to read
+#[cfg(feature = "iced-ui")]
+pub fn read_bytes(file: &FileNode, limit: u64) -> Result<Vec<u8>, std::io::Error> {
+    read_bytes_at(file, 0, limit)
+}
 
-    Ok(nodes)
+/// Reads up to `limit` bytes of a file node starting at `offset`, whether it is
+/// on disk or within an archive
+///
+/// # Arguments
+///
+/// * `file` - The file to read
+/// * `offset` - The byte offset to start reading at
+/// * `limit` - The maximum number of bytes to read
+#[cfg(feature = "iced-ui")]
+pub fn read_bytes_at(file: &FileNode, offset: u64, limit: u64) -> Result<Vec<u8>, std::io::Error> {
+    match &file.source {
+        FileSource::Disk => {
+            let mut file = File::open(&file.absolute_path)?;
+            file.seek(SeekFrom::Start(offset))?;
+
+            let mut bytes = Vec::new();
+            file.take(limit).read_to_end(&mut bytes)?;
+            Ok(bytes)
+        }
+        // Archive entries are compressed, so the bytes before the offset are read too
+        FileSource::Archive {
+            archive_path,
+            entry_path,
+        } => {
+            let mut bytes =
+                read_archive_entry(archive_path, entry_path, offset.saturating_add(limit))?;
+            bytes.drain(..(offset as usize).min(bytes.len()));
+            Ok(bytes)
+        }
+    }
 }
 
 /// Determines the file type based on the file extension
@@ -142,8 +663,1308 @@ pub fn read_dir(path: &String) -> Result<Vec<FileNode>, std::io::Error> {
 /// # Arguments
 ///
 /// * `path` - The path to the file
+#[cfg(feature = "iced-ui")]
 pub fn determine_file_type(path: &String) -> Option<String> {
     let extension = Path::new(path).extension()?;
 
     extension.to_str().map(|s| s.to_string())
 }
+
+/// Well-known file names and the language they are highlighted as, for files
+/// without a useful extension. Each language must be one the loaded syntaxes
+/// know: those without a syntax of their own (Dockerfiles and ignore files)
+/// use the shell syntax, which highlights their comments and commands.
+#[cfg(feature = "iced-ui")]
+pub(crate) const FILE_NAME_LANGUAGES: [(&str, &str); 14] = [
+    ("Dockerfile", "bash"),
+    ("Makefile", "makefile"),
+    ("makefile", "makefile"),
+    ("GNUmakefile", "makefile"),
+    ("Rakefile", "ruby"),
+    ("Gemfile", "ruby"),
+    ("Vagrantfile", "ruby"),
+    ("Jenkinsfile", "groovy"),
+    (".bashrc", "bash"),
+    (".bash_profile", "bash"),
+    (".zshrc", "bash"),
+    (".profile", "bash"),
+    (".gitignore", "bash"),
+    (".dockerignore", "bash"),
+];
+
+/// Extensions and the language they are highlighted as, for extensions the
+/// syntaxes do not list themselves
+#[cfg(feature = "iced-ui")]
+pub(crate) const EXTENSION_LANGUAGES: [(&str, &str); 6] = [
+    ("rs", "rust"),
+    ("mjs", "javascript"),
+    ("cjs", "javascript"),
+    ("jsx", "javascript"),
+    ("jsonc", "json"),
+    ("zsh", "bash"),
+];
+
+/// Determines the language a file is highlighted as. Well-known file names and
+/// extensions are looked up first, then the extension itself is used as the
+/// language, falling back to "txt" for files without an extension.
+///
+/// # Arguments
+///
+/// * `path` - The path to the file
+#[cfg(feature = "iced-ui")]
+pub fn determine_language(path: &str) -> String {
+    let path = Path::new(path);
+
+    let file_name = path.file_name().and_then(|name| name.to_str());
+    if let Some((_, language)) = FILE_NAME_LANGUAGES
+        .iter()
+        .find(|(name, _)| Some(*name) == file_name)
+    {
+        return String::from(*language);
+    }
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => {
+            let extension = extension.to_lowercase();
+            EXTENSION_LANGUAGES
+                .iter()
+                .find(|(known, _)| *known == extension)
+                .map_or(extension, |(_, language)| String::from(*language))
+        }
+        None => String::from("txt"),
+    }
+}
+
+/// Script interpreters and the language their scripts are highlighted as
+#[cfg(feature = "iced-ui")]
+const INTERPRETER_LANGUAGES: [(&str, &str); 10] = [
+    ("sh", "bash"),
+    ("bash", "bash"),
+    ("zsh", "bash"),
+    ("dash", "bash"),
+    ("ksh", "bash"),
+    ("python", "python"),
+    ("node", "javascript"),
+    ("nodejs", "javascript"),
+    ("ruby", "ruby"),
+    ("perl", "perl"),
+];
+
+/// Determines the language of a script from its shebang line (such as
+/// `#!/usr/bin/env python3`). Interpreters without a known language are
+/// returned as they are, without their version.
+///
+/// # Arguments
+///
+/// * `first_line` - The first line of the script
+#[cfg(feature = "iced-ui")]
+pub fn shebang_language(first_line: &str) -> Option<String> {
+    let mut words = first_line.strip_prefix("#!")?.split_whitespace();
+
+    let mut program = Path::new(words.next()?).file_name()?.to_str()?;
+    // `env` runs the interpreter named after its options
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-'))?;
+    }
+
+    // Drop versions such as in `python3` or `python3.12`
+    let interpreter = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    if interpreter.is_empty() {
+        return None;
+    }
+
+    let language = INTERPRETER_LANGUAGES
+        .iter()
+        .find(|(known, _)| *known == interpreter)
+        .map_or(interpreter, |(_, language)| *language);
+    Some(String::from(language))
+}
+
+/// The largest file (in bytes) that can be copied as a data URI
+#[cfg(feature = "iced-ui")]
+pub const DATA_URI_MAX_SIZE: u64 = 256 * 1024;
+
+/// The MIME types of the extensions commonly embedded as data URIs
+#[cfg(feature = "iced-ui")]
+const MIME_TYPES: [(&str, &str); 20] = [
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("bmp", "image/bmp"),
+    ("ico", "image/x-icon"),
+    ("svg", "image/svg+xml"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+    ("txt", "text/plain"),
+    ("html", "text/html"),
+    ("css", "text/css"),
+    ("csv", "text/csv"),
+    ("js", "text/javascript"),
+    ("json", "application/json"),
+    ("xml", "application/xml"),
+    ("pdf", "application/pdf"),
+];
+
+/// Returns the MIME type of a file based on its extension. Unknown extensions
+/// are treated as arbitrary binary data.
+///
+/// # Arguments
+///
+/// * `path` - The path to the file
+#[cfg(feature = "iced-ui")]
+pub fn mime_type(path: &String) -> &'static str {
+    determine_file_type(path)
+        .map(|extension| extension.to_lowercase())
+        .and_then(|extension| {
+            MIME_TYPES
+                .iter()
+                .find(|(known, _)| *known == extension)
+                .map(|(_, mime_type)| *mime_type)
+        })
+        .unwrap_or("application/octet-stream")
+}
+
+/// Encodes bytes as a base64 `data:` URI
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to encode
+/// * `mime_type` - The MIME type of the bytes
+#[cfg(feature = "iced-ui")]
+pub fn data_uri(bytes: &[u8], mime_type: &str) -> String {
+    format!("data:{};base64,{}", mime_type, STANDARD.encode(bytes))
+}
+
+/// Replaces a leading `~` with the home directory of the user. Other paths
+/// (including `~user`) are returned as they are.
+///
+/// # Arguments
+///
+/// * `path` - The typed path
+#[cfg(feature = "iced-ui")]
+pub fn expand_home(path: &str) -> String {
+    let rest = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', MAIN_SEPARATOR]) => rest,
+        _ => return String::from(path),
+    };
+
+    match dirs::home_dir() {
+        Some(home) => format!("{}{}", home.display(), rest),
+        None => String::from(path),
+    }
+}
+
+/// Resolves a typed path to an existing absolute path. `~` is expanded and
+/// relative paths are resolved against `base`.
+///
+/// # Arguments
+///
+/// * `input` - The typed path
+/// * `base` - The directory relative paths start from
+#[cfg(feature = "iced-ui")]
+pub fn resolve_typed_path(input: &str, base: &str) -> Result<String, std::io::Error> {
+    let expanded = expand_home(input.trim());
+    let path = Path::new(base).join(expanded);
+    Ok(canonicalize(path)?.to_string_lossy().into_owned())
+}
+
+/// Returns the candidate completions for a partially typed path. Each candidate
+/// is the full path of a sibling entry whose name starts with the last path
+/// component. Directories are suffixed with the path separator so completion can
+/// continue into them. If `partial` ends with a separator, the entries within that
+/// directory are returned.
+///
+/// # Arguments
+///
+/// * `partial` - The partially typed path
+#[cfg(feature = "iced-ui")]
+pub fn complete_path(partial: &str) -> Vec<String> {
+    let (dir, prefix) = if partial.is_empty() || partial.ends_with(MAIN_SEPARATOR) {
+        (Path::new(partial), "")
+    } else {
+        let path = Path::new(partial);
+        let prefix = path.file_name().and_then(|p| p.to_str()).unwrap_or("");
+        (path.parent().unwrap_or(Path::new("")), prefix)
+    };
+
+    // An empty parent means the path is relative to the CWD
+    let read_path = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+
+    let entries = match fs::read_dir(read_path) {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut candidates: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if !name.starts_with(prefix) {
+                return None;
+            }
+            let mut candidate = String::from(dir.join(&name).to_str()?);
+            if entry.path().is_dir() {
+                candidate.push(MAIN_SEPARATOR);
+            }
+            Some(candidate)
+        })
+        .collect();
+
+    candidates.sort();
+    candidates
+}
+
+/// Splits a path into its breadcrumbs, as (label, absolute path) pairs from the
+/// root down. The root (`/` or a drive such as `C:\`) is the first crumb.
+///
+/// # Arguments
+///
+/// * `path` - The absolute path to split
+#[cfg(feature = "iced-ui")]
+pub fn breadcrumbs(path: &str) -> Vec<(String, String)> {
+    let mut crumbs: Vec<(String, String)> = Vec::new();
+    let mut current = PathBuf::new();
+
+    for component in Path::new(path).components() {
+        current.push(component);
+        let current_path = current.to_string_lossy().into_owned();
+
+        match component {
+            // A drive and its root directory make up a single crumb
+            Component::RootDir if !crumbs.is_empty() => {
+                if let Some(crumb) = crumbs.last_mut() {
+                    crumb.0.push(MAIN_SEPARATOR);
+                    crumb.1 = current_path;
+                }
+            }
+            Component::RootDir => crumbs.push((String::from(MAIN_SEPARATOR), current_path)),
+            other => crumbs.push((
+                other.as_os_str().to_string_lossy().into_owned(),
+                current_path,
+            )),
+        }
+    }
+
+    crumbs
+}
+
+/// Returns the longest prefix shared by all of the given strings
+///
+/// # Arguments
+///
+/// * `values` - The strings to compare
+#[cfg(feature = "iced-ui")]
+pub fn longest_common_prefix(values: &[String]) -> String {
+    let Some(first) = values.first() else {
+        return String::new();
+    };
+
+    let mut prefix_len = first.len();
+    for value in &values[1..] {
+        prefix_len = first
+            .char_indices()
+            .zip(value.chars())
+            .take_while(|((_, a), b)| a == b)
+            .map(|((i, a), _)| i + a.len_utf8())
+            .last()
+            .unwrap_or(0)
+            .min(prefix_len);
+    }
+
+    String::from(&first[..prefix_len])
+}
+
+/// Opens the file with the default application of the operating system. The
+/// launched process is not waited on.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to open
+#[cfg(feature = "iced-ui")]
+pub fn open_externally(path: &str) -> Result<(), std::io::Error> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", ""]);
+        c
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+
+    command.arg(path).spawn().map(|_| ())
+}
+
+/// Opens the file with a command (such as `code -n`), passing the path as its
+/// last argument. An empty command opens the file with the default application
+/// of the operating system. The launched process is not waited on.
+///
+/// # Arguments
+///
+/// * `command` - The program to run followed by its arguments
+/// * `path` - The path of the file to open
+#[cfg(feature = "iced-ui")]
+pub fn open_with(command: &str, path: &str) -> Result<(), std::io::Error> {
+    let mut words = command.split_whitespace();
+    let Some(program) = words.next() else {
+        return open_externally(path);
+    };
+
+    Command::new(program)
+        .args(words)
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+}
+
+/// Formats bytes as a hex dump of 16 bytes per row, with the offset, hex and
+/// ASCII columns.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to format
+/// * `start` - The offset of the first byte within its file
+#[cfg(feature = "iced-ui")]
+pub fn hex_dump(bytes: &[u8], start: u64) -> String {
+    let mut dump = String::new();
+
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        let hex = chunk
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let ascii = chunk
+            .iter()
+            .map(|b| {
+                if b.is_ascii_graphic() || *b == b' ' {
+                    *b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
+
+        let offset = start + row as u64 * 16;
+        dump.push_str(&format!("{:08x}  {:<47}  {}\n", offset, hex, ascii));
+    }
+
+    dump
+}
+
+/// The default maximum depth of recursive directory walks
+pub const DEFAULT_MAX_WALK_DEPTH: usize = 64;
+
+/// An entry found while walking a directory tree
+#[derive(Clone, Debug)]
+pub struct WalkEntry {
+    /// The path of the entry
+    pub path: PathBuf,
+    /// The depth of the entry below the root (children of the root are at depth 1)
+    pub depth: usize,
+    /// A flag to indicate if this entry is a directory
+    pub is_dir: bool,
+}
+
+/// Walks a directory tree iteratively using an explicit work stack, so
+/// pathological trees can't overflow the call stack. Symlinked directories
+/// are not followed unless `follow_symlinks` is set, in which case each
+/// directory is only visited once to avoid symlink loops. Directories deeper
+/// than `max_depth` are not descended into.
+#[derive(Debug)]
+pub struct DirWalker {
+    /// The directories that still have to be read, with their depth
+    stack: Vec<(PathBuf, usize)>,
+    /// The entries that were read but not yielded yet
+    entries: Vec<WalkEntry>,
+    /// The maximum depth to descend to
+    max_depth: usize,
+    /// A flag set when a directory was skipped because of `max_depth`
+    pub depth_limit_reached: bool,
+    /// A flag to descend into symlinked directories
+    follow_symlinks: bool,
+    /// The canonical paths of the directories descended into (when following symlinks)
+    visited: HashSet<PathBuf>,
+    /// The token to stop the walk with
+    cancel: Option<CancelToken>,
+    /// A flag set when the walk stopped early as its token was cancelled
+    pub cancelled: bool,
+}
+
+/// Directory Walker methods
+impl DirWalker {
+    /// Creates a walker over the descendants of `root`
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The directory to walk
+    /// * `max_depth` - The maximum depth to descend to
+    pub fn new(root: impl Into<PathBuf>, max_depth: usize) -> DirWalker {
+        DirWalker {
+            stack: vec![(root.into(), 0)],
+            entries: Vec::new(),
+            max_depth,
+            depth_limit_reached: false,
+            follow_symlinks: false,
+            visited: HashSet::new(),
+            cancel: None,
+            cancelled: false,
+        }
+    }
+
+    /// Sets the token to stop the walk with. It is checked before each entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `cancel` - The token to stop the walk with
+    pub fn cancel_token(mut self, cancel: CancelToken) -> DirWalker {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Sets whether the walker descends into symlinked directories
+    ///
+    /// # Arguments
+    ///
+    /// * `follow_symlinks` - A flag to descend into symlinked directories
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> DirWalker {
+        self.follow_symlinks = follow_symlinks;
+        if follow_symlinks && let Some((root, _)) = self.stack.first() {
+            self.visited.extend(canonicalize(root).ok());
+        }
+        self
+    }
+
+    /// Returns true if the directory was not descended into yet. Always true
+    /// when symlinks are not followed, as a directory can then only be reached once.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory about to be descended into
+    fn first_visit(&mut self, dir: &Path) -> bool {
+        if !self.follow_symlinks {
+            return true;
+        }
+        match canonicalize(dir) {
+            Ok(canonical) => self.visited.insert(canonical),
+            Err(_) => false,
+        }
+    }
+}
+
+impl Iterator for DirWalker {
+    type Item = WalkEntry;
+
+    fn next(&mut self) -> Option<WalkEntry> {
+        loop {
+            if self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+                self.cancelled = true;
+                return None;
+            }
+
+            if let Some(entry) = self.entries.pop() {
+                if entry.is_dir {
+                    if entry.depth < self.max_depth {
+                        if self.first_visit(&entry.path) {
+                            self.stack.push((entry.path.clone(), entry.depth));
+                        } else {
+                            log::debug!("walk: skipping {}, already visited", entry.path.display());
+                        }
+                    } else if !self.depth_limit_reached {
+                        log::warn!(
+                            "walk: maximum depth of {} reached at {}",
+                            self.max_depth,
+                            entry.path.display()
+                        );
+                        self.depth_limit_reached = true;
+                    }
+                }
+                return Some(entry);
+            }
+
+            let (dir, depth) = self.stack.pop()?;
+            let read = match fs::read_dir(&dir) {
+                Ok(r) => r,
+                Err(e) => {
+                    log::warn!("walk: could not read {}: {}", dir.display(), e);
+                    continue;
+                }
+            };
+
+            for entry in read.flatten() {
+                // `file_type` does not follow symlinks
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                let path = entry.path();
+                let is_dir = if file_type.is_symlink() && self.follow_symlinks {
+                    path.is_dir()
+                } else {
+                    file_type.is_dir()
+                };
+                self.entries.push(WalkEntry {
+                    path,
+                    depth: depth + 1,
+                    is_dir,
+                });
+            }
+        }
+    }
+}
+
+/// Computes the total size of the files within a directory
+///
+/// # Arguments
+///
+/// * `path` - The directory to measure
+/// * `max_depth` - The maximum depth to descend to
+/// * `follow_symlinks` - A flag to measure the targets of symbolic links
+/// * `cancel` - The token to stop measuring with
+///
+/// Returns the size in bytes and whether the depth limit was reached (in which
+/// case the size is incomplete)
+pub fn directory_size(
+    path: &str,
+    max_depth: usize,
+    follow_symlinks: bool,
+    cancel: &CancelToken,
+) -> Cancelable<(u64, bool)> {
+    let mut walker = DirWalker::new(path, max_depth)
+        .follow_symlinks(follow_symlinks)
+        .cancel_token(cancel.clone());
+    let size = walker
+        .by_ref()
+        .filter(|entry| !entry.is_dir)
+        .filter_map(|entry| {
+            if follow_symlinks {
+                fs::metadata(&entry.path).ok()
+            } else {
+                fs::symlink_metadata(&entry.path).ok()
+            }
+        })
+        .map(|metadata| metadata.len())
+        .sum();
+
+    if walker.cancelled {
+        return Cancelable::Cancelled;
+    }
+    Cancelable::Completed((size, walker.depth_limit_reached))
+}
+
+/// The maximum number of lines shown in the preview of a file
+#[cfg(feature = "iced-ui")]
+pub const PREVIEW_MAX_LINES: usize = 20;
+
+/// Files larger than this are not previewed
+#[cfg(feature = "iced-ui")]
+const PREVIEW_MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// The maximum number of bytes read to preview a file
+#[cfg(feature = "iced-ui")]
+const PREVIEW_MAX_BYTES: u64 = 4 * 1024;
+
+/// Reads the first lines of a text file for a quick preview. Returns `None` for
+/// large files and binaries, so previewing stays cheap.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to preview
+/// * `max_lines` - The maximum number of lines to return
+#[cfg(feature = "iced-ui")]
+pub fn text_preview(path: &str, max_lines: usize) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > PREVIEW_MAX_FILE_SIZE {
+        return None;
+    }
+
+    let mut bytes = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(PREVIEW_MAX_BYTES)
+        .read_to_end(&mut bytes)
+        .ok()?;
+
+    // A NUL byte is a good sign that the file is not text
+    if bytes.contains(&0) {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(&bytes);
+    let preview = text.lines().take(max_lines).collect::<Vec<_>>().join("\n");
+    (!preview.trim().is_empty()).then_some(preview)
+}
+
+/// The maximum number of bytes of each file read by a content search
+#[cfg(feature = "iced-ui")]
+pub const CONTENT_SEARCH_MAX_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Returns true if the text of a file contains the term, ignoring case. Only
+/// the first [`CONTENT_SEARCH_MAX_BYTES`] are searched, and files that are not
+/// UTF-8 never match.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to search
+/// * `term` - The lowercase term to search for
+#[cfg(feature = "iced-ui")]
+pub fn file_contains(path: &str, term: &str) -> bool {
+    let mut bytes = Vec::new();
+    let read = File::open(path)
+        .and_then(|file| file.take(CONTENT_SEARCH_MAX_BYTES).read_to_end(&mut bytes));
+    if read.is_err() {
+        return false;
+    }
+
+    let text = match std::str::from_utf8(&bytes) {
+        Ok(text) => text,
+        // The read may have stopped in the middle of a character
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+    text.to_lowercase().contains(term)
+}
+
+/// The default maximum number of results of a recursive search
+pub const DEFAULT_MAX_SEARCH_RESULTS: usize = 1000;
+
+/// The results of a recursive search
+#[derive(Debug, Clone)]
+pub struct SearchResults {
+    /// The matching nodes, in the order they were found
+    pub nodes: Vec<FileNode>,
+    /// A flag set when the search stopped at the maximum number of results
+    pub truncated: bool,
+}
+
+/// Searches the descendants of a directory for file names accepted by `matches`.
+/// The walk stops as soon as more than `max_results` nodes match, so huge trees
+/// don't have to be walked completely.
+///
+/// # Arguments
+///
+/// * `root` - The directory to search
+/// * `max_depth` - The maximum depth to descend to
+/// * `follow_symlinks` - A flag to search within symlinked directories
+/// * `max_results` - The maximum number of nodes to return
+/// * `cancel` - The token to stop searching with
+/// * `matches` - Returns true for the file names to include in the results
+pub fn search_tree(
+    root: &str,
+    max_depth: usize,
+    follow_symlinks: bool,
+    max_results: usize,
+    cancel: &CancelToken,
+    matches: impl Fn(&str) -> bool,
+) -> Cancelable<SearchResults> {
+    let mut nodes = Vec::new();
+    let mut walker = DirWalker::new(root, max_depth)
+        .follow_symlinks(follow_symlinks)
+        .cancel_token(cancel.clone());
+
+    for entry in walker.by_ref() {
+        let Some(name) = entry.path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if !matches(name) {
+            continue;
+        }
+        if nodes.len() == max_results {
+            return Cancelable::Completed(SearchResults {
+                nodes,
+                truncated: true,
+            });
+        }
+
+        // Links keep their own path, like in a listing
+        match FileNode::from_path(&entry.path.to_string_lossy(), false) {
+            Ok(node) => nodes.push(node),
+            Err(e) => log::warn!("search: could not read {}: {}", entry.path.display(), e),
+        }
+    }
+
+    if walker.cancelled {
+        return Cancelable::Cancelled;
+    }
+    Cancelable::Completed(SearchResults {
+        nodes,
+        truncated: false,
+    })
+}
+
+/// A text encoding that files can be decoded with
+#[cfg(feature = "iced-ui")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextEncoding(pub &'static Encoding);
+
+#[cfg(feature = "iced-ui")]
+impl std::fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.name())
+    }
+}
+
+/// Returns the encodings users can pick to decode a file with
+#[cfg(feature = "iced-ui")]
+pub fn supported_encodings() -> Vec<TextEncoding> {
+    [
+        encoding_rs::UTF_8,
+        encoding_rs::UTF_16LE,
+        encoding_rs::UTF_16BE,
+        encoding_rs::WINDOWS_1252,
+        encoding_rs::ISO_8859_2,
+        encoding_rs::ISO_8859_15,
+        encoding_rs::WINDOWS_1251,
+        encoding_rs::KOI8_R,
+        encoding_rs::SHIFT_JIS,
+        encoding_rs::EUC_JP,
+        encoding_rs::EUC_KR,
+        encoding_rs::GBK,
+        encoding_rs::BIG5,
+    ]
+    .into_iter()
+    .map(TextEncoding)
+    .collect()
+}
+
+/// Decodes the bytes of a text file. If no encoding is given, it is detected
+/// from the bytes, falling back to a lossy UTF-8 decode when the detection is
+/// inconclusive.
+///
+/// # Arguments
+///
+/// * `bytes` - The contents of the file
+/// * `encoding` - The encoding to decode with, or `None` to detect it
+///
+/// Returns the decoded text and the encoding that was used
+#[cfg(feature = "iced-ui")]
+pub fn decode_text(bytes: &[u8], encoding: Option<TextEncoding>) -> (String, TextEncoding) {
+    let encoding = encoding
+        .map(|e| e.0)
+        .unwrap_or_else(|| detect_encoding(bytes));
+    let (text, used, _) = encoding.decode(bytes);
+
+    (text.into_owned(), TextEncoding(used))
+}
+
+/// The number of bytes at the start of a file checked for binary data
+#[cfg(feature = "iced-ui")]
+const BINARY_CHECK_BYTES: usize = 8 * 1024;
+
+/// Returns true if the bytes of a file look like binary data rather than text,
+/// as there is a NUL byte within the first [`BINARY_CHECK_BYTES`]. Text starting
+/// with a byte order mark is never binary, as UTF-16 text is full of NUL bytes.
+///
+/// # Arguments
+///
+/// * `bytes` - The contents of the file
+#[cfg(feature = "iced-ui")]
+pub fn is_binary(bytes: &[u8]) -> bool {
+    if Encoding::for_bom(bytes).is_some() {
+        return false;
+    }
+    bytes[..bytes.len().min(BINARY_CHECK_BYTES)].contains(&0)
+}
+
+/// Detects the encoding of the bytes of a text file
+///
+/// # Arguments
+///
+/// * `bytes` - The contents of the file
+#[cfg(feature = "iced-ui")]
+fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return encoding_rs::UTF_8;
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    match detector.guess_assess(None, true) {
+        (encoding, true) => encoding,
+        // Decoding as UTF-8 replaces the invalid sequences
+        (_, false) => encoding_rs::UTF_8,
+    }
+}
+
+/// The metadata of a file shown in the details of the viewer. Fields the
+/// platform (or filesystem) doesn't record are `None`.
+#[cfg(feature = "iced-ui")]
+#[derive(Clone, Debug)]
+pub struct FileDetails {
+    /// The absolute path of the file
+    pub path: String,
+    /// The size of the file in bytes
+    pub size_bytes: u64,
+    /// When the file was last modified
+    pub modified_at: Option<String>,
+    /// When the file was created
+    pub created_at: Option<String>,
+    /// The permissions of the file, in octal on Unix
+    pub permissions: Option<String>,
+    /// The MIME type of the file, based on its extension
+    pub mime_type: &'static str,
+}
+
+/// Reads the details of a file from its metadata
+///
+/// # Arguments
+///
+/// * `path` - The absolute path of the file
+#[cfg(feature = "iced-ui")]
+pub fn file_details(path: &String) -> Result<FileDetails, std::io::Error> {
+    let metadata = fs::metadata(path)?;
+
+    #[cfg(unix)]
+    let permissions = {
+        use std::os::unix::fs::PermissionsExt;
+        Some(format!("{:o}", metadata.permissions().mode() & 0o7777))
+    };
+    // Other platforms only tell whether the file is read-only
+    #[cfg(not(unix))]
+    let permissions = metadata
+        .permissions()
+        .readonly()
+        .then(|| String::from("Read-only"));
+
+    Ok(FileDetails {
+        path: path.clone(),
+        size_bytes: metadata.len(),
+        modified_at: metadata.modified().ok().map(|time| format_time(Some(time))),
+        created_at: metadata.created().ok().map(|time| format_time(Some(time))),
+        permissions,
+        mime_type: mime_type(path),
+    })
+}
+
+/// The space of the volume a path is on
+#[cfg(feature = "iced-ui")]
+#[derive(Clone, Copy, Debug)]
+pub struct DiskSpace {
+    /// The number of bytes still available
+    pub available: u64,
+    /// The total number of bytes of the volume
+    pub total: u64,
+}
+
+/// Returns the space of the volume containing the path, or `None` if it can't
+/// be determined.
+///
+/// # Arguments
+///
+/// * `path` - An absolute path on the volume
+#[cfg(feature = "iced-ui")]
+pub fn disk_space(path: &str) -> Option<DiskSpace> {
+    let disks = Disks::new_with_refreshed_list();
+
+    // The volume is the disk with the most specific mount point containing the path
+    disks
+        .list()
+        .iter()
+        .filter(|disk| Path::new(path).starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| DiskSpace {
+            available: disk.available_space(),
+            total: disk.total_space(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{file_node, temp_dir};
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn longest_common_prefix_of_no_strings_is_empty() {
+        assert_eq!(longest_common_prefix(&[]), "");
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn longest_common_prefix_of_one_string_is_the_string() {
+        assert_eq!(longest_common_prefix(&[String::from("notes")]), "notes");
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn longest_common_prefix_stops_at_the_first_difference() {
+        let values = [
+            String::from("/home/user/Documents"),
+            String::from("/home/user/Downloads"),
+            String::from("/home/user/Do"),
+        ];
+        assert_eq!(longest_common_prefix(&values), "/home/user/Do");
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn longest_common_prefix_keeps_whole_characters() {
+        let values = [String::from("café"), String::from("cafè")];
+        assert_eq!(longest_common_prefix(&values), "caf");
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn complete_path_lists_matching_siblings() {
+        let dir = temp_dir("complete-path-siblings");
+        fs::create_dir(dir.join("docs")).unwrap();
+        fs::write(dir.join("downloads.txt"), "").unwrap();
+        fs::write(dir.join("music.txt"), "").unwrap();
+
+        let partial = dir.join("do").to_string_lossy().into_owned();
+        let candidates = complete_path(&partial);
+
+        let docs = format!("{}{}", dir.join("docs").display(), MAIN_SEPARATOR);
+        let downloads = dir.join("downloads.txt").to_string_lossy().into_owned();
+        assert_eq!(candidates, vec![docs, downloads]);
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn complete_path_lists_the_entries_of_a_directory() {
+        let dir = temp_dir("complete-path-entries");
+        fs::write(dir.join("a.txt"), "").unwrap();
+        fs::write(dir.join("b.txt"), "").unwrap();
+
+        let partial = format!("{}{}", dir.display(), MAIN_SEPARATOR);
+        let candidates = complete_path(&partial);
+
+        assert_eq!(candidates.len(), 2);
+        assert!(
+            candidates
+                .iter()
+                .all(|candidate| candidate.starts_with(&partial))
+        );
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn complete_path_of_a_missing_directory_is_empty() {
+        let dir = temp_dir("complete-path-missing");
+        let partial = dir
+            .join("missing")
+            .join("fi")
+            .to_string_lossy()
+            .into_owned();
+
+        assert!(complete_path(&partial).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_dir_of_an_unreadable_directory_is_an_error() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir("read-dir-unreadable");
+        let locked = dir.join("locked");
+        fs::create_dir(&locked).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+
+        // Permissions don't apply to root, which can read the directory anyway
+        let readable = fs::read_dir(&locked).is_ok();
+        let result = read_dir(&locked.to_string_lossy().into_owned());
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        if readable {
+            return;
+        }
+
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn read_dir_of_a_missing_directory_is_an_error() {
+        let dir = temp_dir("read-dir-missing");
+        let missing = dir.join("missing").to_string_lossy().into_owned();
+
+        let error = read_dir(&missing).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn cancelling_stops_a_walk_in_progress() {
+        let dir = temp_dir("walk-cancelled");
+        for name in ["a", "b", "c"] {
+            fs::create_dir_all(dir.join(name).join("nested")).unwrap();
+        }
+
+        let cancel = CancelToken::default();
+        let mut walker = DirWalker::new(&dir, DEFAULT_MAX_WALK_DEPTH).cancel_token(cancel.clone());
+        assert!(walker.next().is_some());
+
+        cancel.cancel();
+
+        assert!(walker.next().is_none());
+        assert!(walker.cancelled);
+    }
+
+    #[test]
+    fn cancelling_a_search_discards_its_results() {
+        let dir = temp_dir("search-cancelled");
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let cancel = CancelToken::default();
+        let results = search_tree(
+            &dir.to_string_lossy(),
+            DEFAULT_MAX_WALK_DEPTH,
+            false,
+            DEFAULT_MAX_SEARCH_RESULTS,
+            &cancel,
+            |_| {
+                // Cancelled as if from the UI while the first match is read
+                cancel.cancel();
+                true
+            },
+        );
+
+        assert!(matches!(results, Cancelable::Cancelled));
+    }
+
+    #[test]
+    fn names_differing_in_case_keep_their_order() {
+        let names = |mut nodes: Vec<FileNode>| {
+            sort_nodes(&mut nodes, SortMode::NameAsc);
+            nodes
+                .into_iter()
+                .map(|node| node.file_name)
+                .collect::<Vec<_>>()
+        };
+
+        let first = names(vec![
+            sort_node("file", false, 0, 0),
+            sort_node("File", false, 0, 0),
+            sort_node("other", false, 0, 0),
+        ]);
+        let second = names(vec![
+            sort_node("other", false, 0, 0),
+            sort_node("File", false, 0, 0),
+            sort_node("file", false, 0, 0),
+        ]);
+
+        assert_eq!(first, vec!["File", "file", "other"]);
+        assert_eq!(first, second);
+    }
+
+    // Only case-sensitive filesystems can hold both `file` and `File`, which
+    // rules out the defaults of macOS and Windows
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reading_names_differing_in_case_again_keeps_their_order() {
+        let dir = temp_dir("sort-case");
+        for name in ["file", "File", "other"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        let path = dir.to_string_lossy().into_owned();
+        let names = |nodes: Vec<FileNode>| {
+            nodes
+                .into_iter()
+                .map(|node| node.file_name)
+                .collect::<Vec<_>>()
+        };
+
+        let first = names(read_dir(&path).unwrap());
+        let second = names(read_dir(&path).unwrap());
+
+        assert_eq!(first, vec!["File", "file", "other"]);
+        assert_eq!(first, second);
+    }
+
+    /// Builds a node of the sort fixture in memory
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the node
+    /// * `is_dir` - A flag to make the node a directory
+    /// * `size` - The size of the node in bytes
+    /// * `modified` - When the node was modified, in seconds since the epoch
+    fn sort_node(name: &str, is_dir: bool, size: u64, modified: u64) -> FileNode {
+        let mut node = file_node(&format!("/project/{}", name), is_dir);
+        node.size_bytes = size;
+        node.modified = Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(modified));
+        node
+    }
+
+    /// Returns the nodes sorted by each test, in no particular order: two
+    /// folders and three files with known sizes and modification times
+    fn sort_fixture() -> Vec<FileNode> {
+        vec![
+            sort_node("b.txt", false, 10, 300),
+            sort_node("src", true, 0, 400),
+            sort_node("a.txt", false, 30, 100),
+            sort_node("Docs", true, 0, 50),
+            sort_node("C.txt", false, 20, 200),
+        ]
+    }
+
+    /// Returns the names of the nodes once sorted in the sort mode
+    fn sorted_names(mut nodes: Vec<FileNode>, mode: SortMode) -> Vec<String> {
+        sort_nodes(&mut nodes, mode);
+        nodes.into_iter().map(|node| node.file_name).collect()
+    }
+
+    #[test]
+    fn sort_by_name_ascending() {
+        let nodes = sort_fixture();
+
+        assert_eq!(
+            sorted_names(nodes, SortMode::NameAsc),
+            vec!["Docs", "src", "a.txt", "b.txt", "C.txt"]
+        );
+    }
+
+    #[test]
+    fn sort_by_name_descending() {
+        let nodes = sort_fixture();
+
+        assert_eq!(
+            sorted_names(nodes, SortMode::NameDesc),
+            vec!["src", "Docs", "C.txt", "b.txt", "a.txt"]
+        );
+    }
+
+    #[test]
+    fn sort_by_size_keeps_folders_by_name() {
+        let nodes = sort_fixture();
+
+        assert_eq!(
+            sorted_names(nodes, SortMode::SizeDesc),
+            vec!["Docs", "src", "a.txt", "C.txt", "b.txt"]
+        );
+    }
+
+    #[test]
+    fn sort_by_modification_time() {
+        let nodes = sort_fixture();
+
+        assert_eq!(
+            sorted_names(nodes, SortMode::ModifiedDesc),
+            vec!["src", "Docs", "b.txt", "C.txt", "a.txt"]
+        );
+    }
+
+    #[test]
+    fn human_size_uses_binary_units() {
+        assert_eq!(human_size(1023), "1023 B");
+        assert_eq!(human_size(1024), "1 KiB");
+        assert_eq!(human_size(1536), "1.5 KiB");
+        assert_eq!(human_size(1024 * 1024), "1 MiB");
+    }
+
+    #[test]
+    fn human_readable_size_of_files() {
+        let mut node = file_node("/project/file.bin", false);
+        let sizes = [
+            (1023, "1023 B"),
+            (1024, "1 KiB"),
+            (1536, "1.5 KiB"),
+            (1024 * 1024, "1 MiB"),
+        ];
+
+        for (size, expected) in sizes {
+            node.size_bytes = size;
+            assert_eq!(node.human_readable_size(), expected);
+        }
+    }
+
+    #[test]
+    fn human_readable_size_of_a_directory_is_a_dash() {
+        let mut node = file_node("/project/src", true);
+        node.size_bytes = 4096;
+
+        assert_eq!(node.human_readable_size(), "—");
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn determine_language_of_well_known_file_names() {
+        assert_eq!(determine_language("/project/Dockerfile"), "bash");
+        assert_eq!(determine_language("/project/Makefile"), "makefile");
+        assert_eq!(determine_language("/project/.gitignore"), "bash");
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn determine_language_of_extensions() {
+        assert_eq!(determine_language("/project/main.rs"), "rust");
+        assert_eq!(determine_language("/project/script.PY"), "py");
+        assert_eq!(determine_language("/project/README"), "txt");
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn shebang_language_of_an_interpreter_path() {
+        assert_eq!(shebang_language("#!/bin/sh"), Some(String::from("bash")));
+        assert_eq!(
+            shebang_language("#!/usr/bin/python3.12"),
+            Some(String::from("python"))
+        );
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn shebang_language_skips_env_and_its_options() {
+        assert_eq!(
+            shebang_language("#!/usr/bin/env python3"),
+            Some(String::from("python"))
+        );
+        assert_eq!(
+            shebang_language("#!/usr/bin/env -S node --no-warnings"),
+            Some(String::from("javascript"))
+        );
+    }
+
+    #[cfg(feature = "iced-ui")]
+    #[test]
+    fn shebang_language_of_other_lines_is_none() {
+        assert_eq!(shebang_language("# A comment"), None);
+        assert_eq!(shebang_language("fn main() {}"), None);
+        assert_eq!(shebang_language("#!"), None);
+    }
+
+    /// Creates a directory nested `depth` levels deep within `dir`
+    fn nest_dirs(dir: &Path, depth: usize) {
+        let mut path = dir.to_path_buf();
+        for _ in 0..depth {
+            path.push("d");
+        }
+        fs::create_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn walking_a_deep_tree_visits_every_level() {
+        let dir = temp_dir("walk-deep");
+        nest_dirs(&dir, 1000);
+
+        let mut walker = DirWalker::new(&dir, usize::MAX);
+        let deepest = walker.by_ref().map(|entry| entry.depth).max();
+
+        assert_eq!(deepest, Some(1000));
+        assert!(!walker.depth_limit_reached);
+    }
+
+    #[test]
+    fn walking_stops_at_the_maximum_depth() {
+        let dir = temp_dir("walk-depth-limit");
+        nest_dirs(&dir, 100);
+
+        let mut walker = DirWalker::new(&dir, 10);
+        let deepest = walker.by_ref().map(|entry| entry.depth).max();
+
+        assert_eq!(deepest, Some(10));
+        assert!(walker.depth_limit_reached);
+    }
+}
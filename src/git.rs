@@ -0,0 +1,120 @@
+use std::{fs::canonicalize, path::Path};
+
+use git2::{Repository, Status, StatusOptions};
+
+use crate::fs_utils::FileNode;
+
+/// The git status of a file within a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GitStatus {
+    /// The file is not tracked by git
+    Untracked,
+    /// The file is new and staged in the index
+    Added,
+    /// The file (or a file within the directory) has been changed
+    Modified,
+}
+
+/// Git Status methods
+impl GitStatus {
+    /// Returns the short marker shown next to the file, like `git status --short`
+    pub fn marker(&self) -> &'static str {
+        match self {
+            GitStatus::Untracked => "??",
+            GitStatus::Added => "A",
+            GitStatus::Modified => "M",
+        }
+    }
+
+    /// Converts the git2 status flags into a [`GitStatus`], if the file has changes
+    fn from_flags(status: Status) -> Option<GitStatus> {
+        if status.is_wt_new() {
+            Some(GitStatus::Untracked)
+        } else if status.is_index_new() {
+            Some(GitStatus::Added)
+        } else if status.intersects(
+            Status::INDEX_MODIFIED
+                | Status::INDEX_DELETED
+                | Status::INDEX_RENAMED
+                | Status::INDEX_TYPECHANGE
+                | Status::WT_MODIFIED
+                | Status::WT_DELETED
+                | Status::WT_RENAMED
+                | Status::WT_TYPECHANGE,
+        ) {
+            Some(GitStatus::Modified)
+        } else {
+            None
+        }
+    }
+}
+
+/// Sets the `git_status` of each node in the directory. Does nothing when the
+/// directory is not within a git repository. Only the entries of `dir` are
+/// scanned so large repositories stay fast.
+///
+/// # Arguments
+///
+/// * `dir` - The absolute path of the directory containing the nodes
+/// * `nodes` - The children of `dir`
+pub fn annotate_git_status(dir: &str, nodes: &mut [FileNode]) {
+    let repo = match Repository::discover(dir) {
+        Ok(repo) => repo,
+        // Not in a repository
+        Err(_) => return,
+    };
+
+    let Some(workdir) = repo.workdir().and_then(|w| canonicalize(w).ok()) else {
+        return;
+    };
+
+    let dir_path = Path::new(dir);
+    let Ok(relative_dir) = dir_path.strip_prefix(&workdir) else {
+        return;
+    };
+
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(false);
+    if !relative_dir.as_os_str().is_empty() {
+        options.pathspec(relative_dir);
+    }
+
+    let statuses = match repo.statuses(Some(&mut options)) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("Could not read git status for {}: {}", dir, e);
+            return;
+        }
+    };
+
+    for entry in statuses.iter() {
+        let (Some(path), Some(status)) = (entry.path(), GitStatus::from_flags(entry.status()))
+        else {
+            continue;
+        };
+
+        // Find the child of `dir` that contains the changed path
+        let full_path = workdir.join(path);
+        let Some(child_name) = full_path
+            .strip_prefix(dir_path)
+            .ok()
+            .and_then(|rest| rest.components().next())
+            .and_then(|c| c.as_os_str().to_str())
+        else {
+            continue;
+        };
+
+        if let Some(node) = nodes.iter_mut().find(|n| n.file_name == child_name) {
+            // Directories show the most significant status of their contents
+            let nested = full_path.parent() != Some(dir_path);
+            let status = if nested && status != GitStatus::Untracked {
+                GitStatus::Modified
+            } else {
+                status
+            };
+            node.git_status = node.git_status.max(Some(status));
+        }
+    }
+}
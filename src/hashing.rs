@@ -0,0 +1,95 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use iced::futures::{SinkExt, Stream, channel::mpsc};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
+
+/// The number of bytes hashed between progress updates
+const HASH_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The algorithms a file can be hashed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HashAlgorithm {
+    /// MD5, which is fast but only suited to detecting accidental changes
+    Md5,
+    /// SHA-256
+    #[default]
+    Sha256,
+}
+
+impl HashAlgorithm {
+    /// All of the algorithms, in the order they are shown to the user
+    pub const ALL: [HashAlgorithm; 2] = [HashAlgorithm::Md5, HashAlgorithm::Sha256];
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            HashAlgorithm::Md5 => "MD5",
+            HashAlgorithm::Sha256 => "SHA-256",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// The events emitted while a file is being hashed
+#[derive(Debug, Clone)]
+pub enum HashEvent {
+    /// The number of bytes hashed so far and the total size of the file
+    Progress { hashed: u64, total: u64 },
+    /// The hashing completed with the hex digest, or failed with an error
+    Finished(Result<String, String>),
+}
+
+/// Hashes a file in chunks on the async runtime, reporting the progress as it goes.
+/// The stream ends with a [`HashEvent::Finished`] event.
+///
+/// # Arguments
+///
+/// * `path` - The file to hash
+/// * `algorithm` - The algorithm to hash the file with
+pub fn hash_file_with_progress(
+    path: PathBuf,
+    algorithm: HashAlgorithm,
+) -> impl Stream<Item = HashEvent> {
+    iced::stream::channel(16, move |mut sender: mpsc::Sender<HashEvent>| async move {
+        let result = match algorithm {
+            HashAlgorithm::Md5 => hash_chunks::<Md5>(&path, &mut sender).await,
+            HashAlgorithm::Sha256 => hash_chunks::<Sha256>(&path, &mut sender).await,
+        };
+        let _ = sender
+            .send(HashEvent::Finished(result.map_err(|e| e.to_string())))
+            .await;
+    })
+}
+
+async fn hash_chunks<D: Digest + Send>(
+    path: &Path,
+    sender: &mut mpsc::Sender<HashEvent>,
+) -> Result<String, std::io::Error> {
+    let mut reader = tokio::fs::File::open(path).await?;
+    let total = reader.metadata().await?.len();
+
+    let mut hasher = D::new();
+    let mut buffer = vec![0u8; HASH_CHUNK_SIZE];
+    let mut hashed = 0;
+    loop {
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        hashed += read as u64;
+        let _ = sender.send(HashEvent::Progress { hashed, total }).await;
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
@@ -0,0 +1,208 @@
+use std::fmt;
+
+use iced::Color;
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Style, Theme, ThemeSet},
+    parsing::{SyntaxReference, SyntaxSet},
+};
+
+/// The syntax highlighting theme picked in the settings
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SyntaxTheme {
+    /// A light or dark theme, following the color mode
+    #[default]
+    Automatic,
+    /// The theme with the given name
+    Named(String),
+}
+
+impl SyntaxTheme {
+    /// Returns the name of the theme to highlight with
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The picked theme
+    /// * `color_mode` - The color mode the automatic theme follows
+    pub fn theme_name(&self, color_mode: dark_light::Mode) -> &str {
+        match self {
+            SyntaxTheme::Automatic => match color_mode {
+                dark_light::Mode::Dark => "base16-ocean.dark",
+                dark_light::Mode::Light => "Solarized (light)",
+                dark_light::Mode::Unspecified => "Solarized (light)",
+            },
+            SyntaxTheme::Named(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for SyntaxTheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyntaxTheme::Automatic => write!(f, "Automatic"),
+            SyntaxTheme::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// The syntax highlighting of a file. It is kept between frames, so the file is
+/// only highlighted again when it or the theme changes.
+#[derive(Debug, Clone)]
+pub struct HighlightedFile {
+    /// The absolute path of the highlighted file
+    pub path: String,
+    /// The name of the theme the file was highlighted with
+    pub theme: String,
+    /// The fragments of each line with their color
+    pub lines: Vec<Vec<(Color, String)>>,
+}
+
+/// Highlights the contents of a file
+///
+/// # Arguments
+///
+/// * `path` - The absolute path of the file
+/// * `contents` - The text of the file
+/// * `language` - The language the syntax is picked by
+/// * `syntax_set` - The loaded syntect syntaxes
+/// * `theme_set` - The loaded syntect themes
+/// * `theme_name` - The name of the theme to highlight with
+pub fn highlight_file(
+    path: &str,
+    contents: &str,
+    language: Option<&str>,
+    syntax_set: &SyntaxSet,
+    theme_set: &ThemeSet,
+    theme_name: &str,
+) -> HighlightedFile {
+    let syntax = find_syntax(syntax_set, language);
+    let default_theme = Theme::default();
+    let theme = find_theme(theme_set, theme_name).unwrap_or(&default_theme);
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    let lines = contents
+        .lines()
+        .map(|line| {
+            highlighter
+                .highlight_line(line, syntax_set)
+                .unwrap_or_else(|_| vec![(Style::default(), line)])
+                .into_iter()
+                .map(|(style, fragment)| {
+                    let color = Color::from_rgb8(
+                        style.foreground.r,
+                        style.foreground.g,
+                        style.foreground.b,
+                    );
+                    (color, String::from(fragment))
+                })
+                .collect()
+        })
+        .collect();
+
+    HighlightedFile {
+        path: String::from(path),
+        theme: String::from(theme_name),
+        lines,
+    }
+}
+
+/// Looks up the syntax of a language by its extension or name, falling back to
+/// plain text for unknown languages (which every syntax set has)
+///
+/// # Arguments
+///
+/// * `syntax_set` - The loaded syntect syntaxes
+/// * `language` - The extension or name of the language
+pub fn find_syntax<'a>(syntax_set: &'a SyntaxSet, language: Option<&str>) -> &'a SyntaxReference {
+    language
+        .and_then(|language| syntax_set.find_syntax_by_token(language))
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text())
+}
+
+/// Looks up a syntax highlighting theme by name. If the theme is missing,
+/// logs a warning and falls back to any available theme instead of panicking.
+///
+/// # Arguments
+///
+/// * `theme_set` - The loaded syntect themes
+/// * `name` - The name of the theme to look up
+fn find_theme<'a>(theme_set: &'a ThemeSet, name: &str) -> Option<&'a Theme> {
+    match theme_set.themes.get(name) {
+        Some(theme) => Some(theme),
+        None => {
+            log::warn!("Theme [{}] not found, falling back to another theme", name);
+            theme_set.themes.values().next()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs_utils::{EXTENSION_LANGUAGES, FILE_NAME_LANGUAGES, determine_language};
+
+    #[test]
+    fn find_syntax_of_a_known_language() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+
+        assert_eq!(find_syntax(&syntax_set, Some("rust")).name, "Rust");
+    }
+
+    #[test]
+    fn find_syntax_falls_back_to_plain_text() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        let plain_text = syntax_set.find_syntax_plain_text();
+
+        assert_eq!(
+            find_syntax(&syntax_set, Some("no-such-language")).name,
+            plain_text.name
+        );
+        assert_eq!(find_syntax(&syntax_set, None).name, plain_text.name);
+    }
+
+    #[test]
+    fn well_known_file_names_have_a_syntax() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        let plain_text = syntax_set.find_syntax_plain_text();
+
+        for (name, _) in FILE_NAME_LANGUAGES {
+            let language = determine_language(&format!("/project/{}", name));
+            let syntax = find_syntax(&syntax_set, Some(&language));
+            assert_ne!(syntax.name, plain_text.name, "{} has no syntax", name);
+        }
+    }
+
+    #[test]
+    fn mapped_extensions_have_a_syntax() {
+        let syntax_set = SyntaxSet::load_defaults_newlines();
+        let plain_text = syntax_set.find_syntax_plain_text();
+
+        for (extension, _) in EXTENSION_LANGUAGES {
+            let language = determine_language(&format!("/project/file.{}", extension));
+            let syntax = find_syntax(&syntax_set, Some(&language));
+            assert_ne!(syntax.name, plain_text.name, ".{} has no syntax", extension);
+        }
+    }
+
+    #[test]
+    fn find_theme_by_name() {
+        let theme_set = ThemeSet::load_defaults();
+        let theme = find_theme(&theme_set, "base16-ocean.dark").unwrap();
+
+        assert!(std::ptr::eq(theme, &theme_set.themes["base16-ocean.dark"]));
+    }
+
+    #[test]
+    fn find_theme_falls_back_to_another_theme() {
+        let theme_set = ThemeSet::load_defaults();
+        let theme = find_theme(&theme_set, "no-such-theme").unwrap();
+
+        assert!(theme_set.themes.values().any(|t| std::ptr::eq(t, theme)));
+    }
+
+    #[test]
+    fn find_theme_without_themes_is_none() {
+        assert!(find_theme(&ThemeSet::default(), "base16-ocean.dark").is_none());
+    }
+}
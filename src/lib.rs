@@ -1,3 +1,76 @@
-pub mod app;
-pub mod fs_utils;
-pub mod ui;
+//! The filesystem logic of the file explorer, usable without its window.
+//!
+//! Directories are read into [`FileNode`]s with [`read_dir`] (or
+//! [`read_dir_cancelable`] from another thread) and ordered with [`sort_nodes`].
+//! Trees are walked with a [`DirWalker`], searched with [`search_tree`] and
+//! compared with [`compare_dirs`]. Files are created, renamed, moved and deleted
+//! through [`create_folder`], [`rename_path`], [`move_path`] and [`delete_path`],
+//! each of which can be undone with an [`UndoableOp`].
+//!
+//! With the `iced-ui` feature (on by default), the crate also holds the state of
+//! the explorer window: `FileExplorerApp`, driven by `Action`s and narrowed down
+//! by its `Filters`. The `rust_gui` binary runs that window on top of this library.
+
+#[cfg(feature = "iced-ui")]
+mod app;
+mod archive;
+#[cfg(feature = "iced-ui")]
+mod associations;
+#[cfg(feature = "iced-ui")]
+mod commands;
+mod compare;
+#[cfg(feature = "iced-ui")]
+mod dialogs;
+#[cfg(feature = "serde")]
+mod export;
+mod fs_ops;
+mod fs_utils;
+mod git;
+#[cfg(feature = "iced-ui")]
+mod hashing;
+#[cfg(feature = "iced-ui")]
+mod highlight;
+#[cfg(feature = "iced-ui")]
+mod logging;
+#[cfg(feature = "iced-ui")]
+mod places;
+#[cfg(feature = "iced-ui")]
+mod recent;
+#[cfg(all(feature = "iced-ui", feature = "serde"))]
+mod settings;
+#[cfg(test)]
+mod test_utils;
+#[cfg(feature = "iced-ui")]
+mod thumbnails;
+#[cfg(feature = "iced-ui")]
+mod ui;
+
+#[cfg(feature = "iced-ui")]
+pub use app::{Action, FileExplorerApp, Filters};
+#[cfg(feature = "iced-ui")]
+pub use archive::{ExtractEvent, extract_with_progress};
+pub use archive::{
+    extract, extraction_folder_name, is_archive, is_extractable, read_archive_dir,
+    read_archive_entry,
+};
+pub use compare::{ComparedEntry, Comparison, Difference, compare_dirs};
+#[cfg(feature = "serde")]
+pub use export::{listing_to_json, walk_listing};
+#[cfg(feature = "iced-ui")]
+pub use fs_ops::{CopyEvent, copy_file_with_progress};
+pub use fs_ops::{
+    PlannedRename, RENAME_PLACEHOLDERS, UndoableOp, apply_batch_rename, check_file_name,
+    create_folder, delete_path, move_path, plan_batch_rename, rename_path, save_copy,
+    unique_destination,
+};
+pub use fs_utils::{
+    CancelToken, Cancelable, DEFAULT_MAX_SEARCH_RESULTS, DEFAULT_MAX_WALK_DEPTH, DirWalker,
+    ExtensionDisplay, FileNode, FileSource, SearchResults, SortMode, WalkEntry, directory_size,
+    has_more_entries_than, human_size, list_dir, read_dir, read_dir_cancelable, search_tree,
+    sort_nodes,
+};
+pub use git::GitStatus;
+#[cfg(feature = "iced-ui")]
+pub use logging::init as init_logging;
+#[cfg(all(feature = "iced-ui", feature = "serde"))]
+pub use settings::Settings;
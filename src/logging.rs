@@ -0,0 +1,94 @@
+use std::{
+    collections::VecDeque,
+    sync::{Mutex, MutexGuard},
+};
+
+use chrono::offset::Local;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// The maximum number of log entries kept for the log panel. Older entries are dropped.
+pub const LOG_CAPACITY: usize = 500;
+
+const TIME_FORMAT: &str = "%H:%M:%S";
+
+/// The logger capturing the messages of the `log` facade
+static LOGGER: RingBufferLogger = RingBufferLogger {
+    entries: Mutex::new(VecDeque::new()),
+};
+
+/// A message captured by the logger
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// The severity of the message
+    pub level: Level,
+    /// When the message was logged
+    pub time: String,
+    /// The message itself
+    pub message: String,
+}
+
+/// A logger writing the most recent messages into a ring buffer (and to stderr)
+struct RingBufferLogger {
+    entries: Mutex<VecDeque<LogEntry>>,
+}
+
+impl RingBufferLogger {
+    /// Locks the entries, recovering them if another thread panicked while logging
+    fn lock(&self) -> MutexGuard<'_, VecDeque<LogEntry>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Log for RingBufferLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Only keep the messages of this application, not those of its dependencies
+        metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let entry = LogEntry {
+            level: record.level(),
+            time: Local::now().format(TIME_FORMAT).to_string(),
+            message: record.args().to_string(),
+        };
+
+        // Keep the console output for those running from a terminal
+        eprintln!("[{}] {}", entry.level, entry.message);
+
+        let mut entries = self.lock();
+        if entries.len() == LOG_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the logger. Messages logged before this is called are lost.
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Debug);
+    }
+}
+
+/// Returns a copy of the captured log entries, oldest first
+pub fn entries() -> Vec<LogEntry> {
+    LOGGER.lock().iter().cloned().collect()
+}
+
+/// Returns the number of captured log entries
+pub fn entry_count() -> usize {
+    LOGGER.lock().len()
+}
+
+/// Removes all of the captured log entries
+pub fn clear() {
+    LOGGER.lock().clear();
+}
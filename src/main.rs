@@ -1,21 +1,25 @@
-use crate::app::FileExplorerApp;
+use rust_gui::FileExplorerApp;
 
-// Import modules for this application
-
-// The application struct itself
-mod app;
-// The filesystem utilities and structures
-mod fs_utils;
-// The UI rendering code which gets attached to the FileExplorerApp
-mod ui;
-
-/// The Entrypoint of the application. Reads the CWD for files and
-/// constructs a GUI Window with the Application state.
+/// The Entrypoint of the application. Reads the CWD (or the path passed as the
+/// first argument) for files and constructs a GUI Window with the Application state.
 fn main() {
-    let _ = iced::application(
-        FileExplorerApp::default,
+    rust_gui::init_logging();
+
+    #[allow(unused_mut)]
+    let mut application = iced::application(
+        FileExplorerApp::new,
         FileExplorerApp::update,
         FileExplorerApp::view,
     )
-    .run();
+    .title(FileExplorerApp::title)
+    .theme(FileExplorerApp::theme)
+    .subscription(FileExplorerApp::subscription);
+
+    // Open the window at the size it had when it was closed
+    #[cfg(feature = "serde")]
+    if let Some(window_size) = rust_gui::Settings::load().window_size {
+        application = application.window_size(window_size);
+    }
+
+    let _ = application.run();
 }
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use sysinfo::Disks;
+
+/// A location listed in the quick access section of the file tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Place {
+    /// The name shown for the location
+    pub name: String,
+    /// The absolute path of the location
+    pub path: String,
+}
+
+/// Returns the common locations of the user (Home, Desktop, Documents and
+/// Downloads) followed by the mounted volumes. Locations that don't exist on
+/// this platform or machine are left out.
+pub fn quick_access_places() -> Vec<Place> {
+    let user_dirs = [
+        ("🏠 Home", dirs::home_dir()),
+        ("🖥️ Desktop", dirs::desktop_dir()),
+        ("📄 Documents", dirs::document_dir()),
+        ("⬇️ Downloads", dirs::download_dir()),
+    ];
+
+    let mut places: Vec<Place> = user_dirs
+        .into_iter()
+        .filter_map(|(name, path)| place(name.to_string(), path?))
+        .collect();
+
+    let disks = Disks::new_with_refreshed_list();
+    for disk in disks.list() {
+        let mount_point = disk.mount_point().to_path_buf();
+        let name = match disk.name().to_string_lossy() {
+            label if label.is_empty() => mount_point.to_string_lossy().into_owned(),
+            label => format!("{} ({})", label, mount_point.display()),
+        };
+
+        // The same volume can be listed more than once (e.g. bind mounts)
+        if let Some(volume) = place(format!("💽 {}", name), mount_point)
+            && !places.iter().any(|p| p.path == volume.path)
+        {
+            places.push(volume);
+        }
+    }
+
+    places
+}
+
+/// Builds a place if its path is an existing directory
+///
+/// # Arguments
+///
+/// * `name` - The name shown for the location
+/// * `path` - The path of the location
+fn place(name: String, path: PathBuf) -> Option<Place> {
+    if !path.is_dir() {
+        return None;
+    }
+
+    Some(Place {
+        name,
+        path: path.to_string_lossy().into_owned(),
+    })
+}
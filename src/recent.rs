@@ -0,0 +1,102 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The maximum number of recently opened files remembered. The oldest are dropped first.
+pub const RECENT_FILES_CAPACITY: usize = 20;
+
+/// The name of the file (in the configuration directory of the application)
+/// holding the recently opened files, one path per line
+const RECENT_FILES_FILE_NAME: &str = "recent_files";
+
+/// The files opened most recently, newest first. The list is saved whenever
+/// it changes so it survives restarts.
+#[derive(Debug, Default)]
+pub struct RecentFiles {
+    paths: VecDeque<String>,
+}
+
+impl RecentFiles {
+    /// Loads the recently opened files saved by a previous session. The list is
+    /// empty if none were saved or they can't be read.
+    pub fn load() -> Self {
+        let paths = storage_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .take(RECENT_FILES_CAPACITY)
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        RecentFiles { paths }
+    }
+
+    /// Moves a path to the front of the list (adding it if needed), drops the
+    /// oldest paths beyond the capacity and saves the list
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The list instance
+    /// * `path` - The absolute path of the opened file
+    pub fn add(&mut self, path: &str) {
+        if self.paths.front().is_some_and(|newest| newest == path) {
+            return;
+        }
+
+        self.paths.retain(|p| p != path);
+        self.paths.push_front(path.to_string());
+        self.paths.truncate(RECENT_FILES_CAPACITY);
+        self.save();
+    }
+
+    /// Returns the recently opened files that still exist, newest first
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The list instance
+    pub fn existing(&self) -> impl Iterator<Item = &String> {
+        self.paths.iter().filter(|path| Path::new(path).exists())
+    }
+
+    /// Writes the list to the configuration directory, logging any failure
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The list instance
+    fn save(&self) {
+        let Some(path) = storage_path() else {
+            log::warn!("No configuration directory to save the recent files in");
+            return;
+        };
+
+        let contents: Vec<&str> = self.paths.iter().map(String::as_str).collect();
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, contents.join("\n")));
+
+        if let Err(e) = result {
+            log::error!(
+                "Could not save the recent files to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Returns the path of the file holding the recently opened files, if the
+/// platform has a configuration directory
+fn storage_path() -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join(env!("CARGO_PKG_NAME"))
+            .join(RECENT_FILES_FILE_NAME),
+    )
+}
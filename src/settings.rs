@@ -0,0 +1,141 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{ColorMode, DirViewPreferences, RowDensity, TreeIndent};
+use crate::associations::WrapMode;
+use crate::fs_utils::SortMode;
+use crate::highlight::SyntaxTheme;
+
+/// The version of the settings written by this build. Fields added later take
+/// their default when missing and unknown fields are ignored, so the version
+/// only needs to change when a field changes meaning.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// The name of the file (in the configuration directory of the application)
+/// holding the settings
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// The settings remembered between sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The version of the application the settings were written by
+    pub version: u32,
+    /// The absolute path of the directory opened last
+    pub last_dir: Option<String>,
+    /// The width and height of the window
+    pub window_size: Option<(f32, f32)>,
+    /// The order the file tree is sorted in, for the directories without
+    /// remembered filters (kept for the settings of older versions)
+    pub sort_mode: SortMode,
+    /// Whether hidden files are shown in the file tree
+    pub show_hidden: bool,
+    /// Whether the application follows the system or is always light or dark
+    pub color_mode: ColorMode,
+    /// How much space each row of the file tree takes
+    pub row_density: RowDensity,
+    /// How far the children of an expanded directory are indented
+    pub tree_indent: TreeIndent,
+    /// Which files wrap their long lines in the viewer
+    pub wrap_mode: WrapMode,
+    /// The theme the viewer highlights text with
+    pub syntax_theme: SyntaxTheme,
+    /// The command files are opened externally with
+    pub external_editor: String,
+    /// The absolute paths of the bookmarked directories, in the order they were added
+    pub bookmarks: Vec<String>,
+    /// The filters remembered for specific directories, by absolute path
+    pub dir_views: HashMap<String, DirViewPreferences>,
+    /// The filters of the directories without remembered ones (missing in the
+    /// settings of older versions, which only saved the `sort_mode`)
+    pub default_dir_view: Option<DirViewPreferences>,
+}
+
+/// The default settings
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            last_dir: None,
+            window_size: None,
+            sort_mode: SortMode::default(),
+            show_hidden: false,
+            color_mode: ColorMode::default(),
+            row_density: RowDensity::default(),
+            tree_indent: TreeIndent::default(),
+            wrap_mode: WrapMode::default(),
+            syntax_theme: SyntaxTheme::default(),
+            external_editor: String::new(),
+            bookmarks: Vec::new(),
+            dir_views: HashMap::new(),
+            default_dir_view: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the settings saved by a previous session. The default settings are
+    /// returned if none were saved or they can't be read.
+    pub fn load() -> Self {
+        let Some(path) = storage_path() else {
+            return Settings::default();
+        };
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Settings::default();
+        };
+
+        match serde_json::from_str::<Settings>(&contents) {
+            Ok(settings) => {
+                if settings.version > SETTINGS_VERSION {
+                    log::warn!(
+                        "The settings in {} were saved by a newer version, some may be ignored",
+                        path.display()
+                    );
+                }
+                settings
+            }
+            Err(e) => {
+                log::error!("Could not read the settings in {}: {}", path.display(), e);
+                Settings::default()
+            }
+        }
+    }
+
+    /// Writes the settings to the configuration directory, logging any failure
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The settings instance
+    pub fn save(&self) {
+        let Some(path) = storage_path() else {
+            log::warn!("No configuration directory to save the settings in");
+            return;
+        };
+
+        let result = serde_json::to_string_pretty(&Settings {
+            version: SETTINGS_VERSION,
+            ..self.clone()
+        })
+        .map_err(std::io::Error::other)
+        .and_then(|contents| {
+            path.parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::write(&path, contents))
+        });
+
+        if let Err(e) = result {
+            log::error!("Could not save the settings to {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Returns the path of the file holding the settings, if the platform has a
+/// configuration directory
+fn storage_path() -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join(env!("CARGO_PKG_NAME"))
+            .join(SETTINGS_FILE_NAME),
+    )
+}
@@ -0,0 +1,58 @@
+use std::{fs, path::PathBuf};
+
+use crate::fs_utils::{FileNode, FileSource};
+
+/// Creates an empty directory for a test within the temporary directory of the
+/// system, removing whatever a previous run left in it
+///
+/// # Arguments
+///
+/// * `name` - A name unique to the test
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "{}-{}-{}",
+        env!("CARGO_PKG_NAME"),
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Could not create the test directory");
+    dir
+}
+
+/// Builds a file node in memory, without reading the disk. Its size and times
+/// are empty until the test sets them.
+///
+/// # Arguments
+///
+/// * `absolute_path` - The path of the node, whose last component is its name
+/// * `is_dir` - A flag to make the node a directory
+pub fn file_node(absolute_path: &str, is_dir: bool) -> FileNode {
+    let path = std::path::Path::new(absolute_path);
+    FileNode {
+        file_name: path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+        absolute_path: String::from(absolute_path),
+        parent_folder: path
+            .parent()
+            .map(|parent| parent.to_string_lossy().into_owned()),
+        is_dir,
+        matches_filters: true,
+        match_score: None,
+        match_indices: Vec::new(),
+        size_bytes: 0,
+        file_size: String::new(),
+        created_at: String::new(),
+        modified_at: String::new(),
+        modified: None,
+        accessed_at: String::new(),
+        git_status: None,
+        source: FileSource::Disk,
+        is_symlink: false,
+        link_target: None,
+        children: None,
+        expanded: false,
+        depth: 0,
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::{HashMap, VecDeque};
+
+use iced::widget::image::Handle;
+
+/// The size (in pixels) of the longest side of a thumbnail
+const THUMBNAIL_SIZE: u32 = 48;
+
+/// The maximum number of thumbnails kept in memory. The oldest are dropped first.
+pub const THUMBNAIL_CACHE_CAPACITY: usize = 1000;
+
+/// Identifies a thumbnail by the path of its image and when it was last modified,
+/// so that images changed on disk get a new thumbnail
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ThumbnailKey {
+    /// The absolute path of the image
+    pub path: String,
+    /// When the image was last modified
+    pub modified_at: String,
+}
+
+/// A bounded cache of thumbnails. An entry holding `None` is either still being
+/// generated or could not be decoded.
+#[derive(Debug, Default)]
+pub struct ThumbnailCache {
+    entries: HashMap<ThumbnailKey, Option<Handle>>,
+    order: VecDeque<ThumbnailKey>,
+}
+
+impl ThumbnailCache {
+    /// Returns the thumbnail for the key, if it was generated
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The cache instance
+    /// * `key` - The key of the thumbnail
+    pub fn get(&self, key: &ThumbnailKey) -> Option<&Handle> {
+        self.entries.get(key).and_then(Option::as_ref)
+    }
+
+    /// Returns true if the thumbnail for the key was generated or requested
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The cache instance
+    /// * `key` - The key of the thumbnail
+    pub fn contains(&self, key: &ThumbnailKey) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Stores a thumbnail (or `None` while it is generated), dropping the oldest
+    /// thumbnails beyond the capacity
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The cache instance
+    /// * `key` - The key of the thumbnail
+    /// * `thumbnail` - The thumbnail
+    pub fn insert(&mut self, key: ThumbnailKey, thumbnail: Option<Handle>) {
+        if self.entries.insert(key.clone(), thumbnail).is_none() {
+            self.order.push_back(key);
+        }
+
+        while self.order.len() > THUMBNAIL_CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Decodes an image and downscales it to a thumbnail, keeping its aspect ratio.
+/// Returns `None` if the image can't be read or decoded.
+///
+/// # Arguments
+///
+/// * `path` - The path of the image
+pub fn generate_thumbnail(path: &str) -> Option<Handle> {
+    let thumbnail = image::open(path)
+        .inspect_err(|e| log::debug!("Could not decode {} for a thumbnail: {}", path, e))
+        .ok()?
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .to_rgba8();
+
+    Some(Handle::from_rgba(
+        thumbnail.width(),
+        thumbnail.height(),
+        thumbnail.into_raw(),
+    ))
+}
@@ -1,8 +1,34 @@
-use crate::app::{Action, ContextMenuAction, FileExplorerApp, PaneContent};
+use crate::app::{
+    Action, COMMAND_PALETTE_INPUT_ID, ColorMode, ContextMenuAction, FILE_CONTENTS_SCROLLABLE_ID,
+    FILE_TREE_SCROLLABLE_ID, FIND_INPUT_ID, FileExplorerApp, HEX_VIEW_WINDOW_OPTIONS, KindFilter,
+    LARGE_DIR_THRESHOLD_OPTIONS, MAX_SEARCH_RESULTS_OPTIONS, MAX_VIEW_FONT_SIZE,
+    MIN_VIEW_FONT_SIZE, NEW_FOLDER_INPUT_ID, NameRegex, PaneContent, RENAME_INPUT_ID, RowDensity,
+    SearchMode, TreeIndent,
+};
+use crate::archive::is_extractable;
+use crate::associations::{OpenBehavior, WrapMode};
+use crate::commands::filter_commands;
+use crate::compare::{Comparison, Difference};
+use crate::fs_ops::RENAME_PLACEHOLDERS;
+use crate::fs_utils::{
+    ExtensionDisplay, FileNode, FileSource, SortMode, breadcrumbs, human_size, mime_type,
+    relative_time, supported_encodings,
+};
+use crate::git::GitStatus;
+use crate::hashing::HashAlgorithm;
+use crate::highlight::SyntaxTheme;
+use crate::logging;
+use crate::thumbnails::ThumbnailKey;
 
-use iced::widget::text::{Rich, Span};
-use iced::widget::{center, mouse_area, opaque, pane_grid, rule, scrollable, stack, text_input};
-use iced::{Alignment, Element, Theme, border};
+use iced::widget::text::{Rich, Span, Wrapping};
+use iced::widget::{
+    center, image, mouse_area, opaque, pane_grid, pick_list, progress_bar, rule, scrollable, stack,
+    text_input, toggler, tooltip,
+};
+use iced::{
+    Alignment, ContentFit, Element, Event, Subscription, Theme, border, event, keyboard, time,
+    window,
+};
 use iced::{
     Background, Color, Font, Length, Task,
     font::Weight,
@@ -10,15 +36,95 @@ use iced::{
     widget::{button, column, container, row, space, span, text},
 };
 use iced_aw::ContextMenu;
+use log::Level;
 
-use syntect::easy::HighlightLines;
+use std::borrow::Cow;
+use std::path::Path;
+use std::time::Duration;
 
 const HEADING_FONT_SIZE: f32 = 32.0;
+
+/// The title of the window when no long operation is in progress
+const APP_TITLE: &str = "File Explorer";
 const FILE_NAME_FONT_SIZE: f32 = 24.0;
 
+/// The height of the log panel when it is shown
+const LOG_PANEL_HEIGHT: f32 = 160.0;
+
+/// The viewers a file can be switched between from the content header
+const RENDER_MODES: [OpenBehavior; 2] = [OpenBehavior::InternalText, OpenBehavior::InternalHex];
+
+/// How much the font size of the viewer changes per step
+const VIEW_FONT_SIZE_STEP: f32 = 2.0;
+
+/// The width of the strip marking the lines matching the find query
+const FIND_STRIP_WIDTH: f32 = 10.0;
+
+/// The height of each marker of the find strip
+const FIND_MARKER_HEIGHT: f32 = 3.0;
+
+/// How often relative times (such as "5m ago") are refreshed
+const RELATIVE_TIME_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The width of the extension column of the file tree
+const EXTENSION_COLUMN_WIDTH: f32 = 60.0;
+
+/// The width of the size column of the file tree
+const SIZE_COLUMN_WIDTH: f32 = 70.0;
+
+/// The width of the modification time column of the file tree
+const MODIFIED_COLUMN_WIDTH: f32 = 130.0;
+
+/// The width of the arrows expanding directories in the file tree
+const TREE_EXPANDER_WIDTH: f32 = 16.0;
+
+/// The size (in pixels) thumbnails are shown at in the file tree
+const THUMBNAIL_DISPLAY_SIZE: f32 = 24.0;
+
+/// The number of positions a marker can take along the find strip
+const FIND_STRIP_BUCKETS: usize = 1000;
+
 impl FileExplorerApp {
     pub fn update(&mut self, action: Action) -> Task<Action> {
-        self.post_update(action)
+        let task = self.post_update(action);
+        #[cfg(feature = "serde")]
+        self.save_settings();
+        // The action may have opened a directory to read (or finished reading
+        // it), or changed the rows shown, which may need thumbnails
+        Task::batch([
+            task,
+            self.load_pending_dir(),
+            self.restore_scroll(),
+            self.load_visible_thumbnails(),
+            self.load_visible_child_counts(),
+        ])
+    }
+
+    pub fn title(&self) -> String {
+        // Long operations show in the title bar (and taskbar) while the window is in the background
+        match self.busy_status() {
+            Some(status) => format!("{} — {}", status, APP_TITLE),
+            None => String::from(APP_TITLE),
+        }
+    }
+
+    pub fn theme(&self) -> iced::Theme {
+        match self.effective_color_mode() {
+            dark_light::Mode::Dark => iced::Theme::Dark,
+            dark_light::Mode::Light | dark_light::Mode::Unspecified => iced::Theme::Light,
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Action> {
+        // iced only redraws on events, so relative times (shown in the file tree)
+        // are refreshed on a timer
+        let relative_times = if self.relative_times {
+            time::every(RELATIVE_TIME_REFRESH_INTERVAL).map(|_| Action::RefreshRelativeTimes)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([event::listen_with(handle_event), relative_times])
     }
 
     pub fn view(&self) -> iced::Element<'_, Action> {
@@ -35,51 +141,197 @@ impl FileExplorerApp {
         .height(Length::Fill)
         .on_resize(10, Action::PanesResized);
 
-        let app_content = row![grid].spacing(20.0).into();
+        // Give the content the whole window when the file tree is hidden
+        let main_content: iced::Element<Action> = if self.sidebar_visible {
+            row![grid].spacing(20.0).into()
+        } else {
+            container(self.file_contents())
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into()
+        };
+
+        let mut app_content = column![main_content];
+        if self.log_panel_open {
+            app_content = app_content.push(self.log_panel());
+        }
+        let app_content = app_content.push(self.status_bar()).into();
 
         if self.file_info_modal_open {
             let modal_content = self.file_info_modal_content();
             modal(app_content, modal_content, Action::CloseFileInfoModal)
+        } else if self.settings_modal_open {
+            let modal_content = self.settings_modal_content();
+            modal(app_content, modal_content, Action::CloseSettingsModal)
+        } else if self.associations_modal_open {
+            let modal_content = self.associations_modal_content();
+            modal(app_content, modal_content, Action::CloseAssociationsModal)
+        } else if let Some(prompt) = &self.large_dir_prompt {
+            let modal_content = self.large_dir_modal_content(&prompt.dir);
+            modal(app_content, modal_content, Action::CancelLargeDir)
+        } else if !self.pending_delete.is_empty() {
+            let modal_content = self.delete_modal_content(&self.pending_delete);
+            modal(app_content, modal_content, Action::CancelDelete)
+        } else if let Some(comparison) = &self.comparison {
+            let modal_content = self.comparison_modal_content(comparison);
+            modal(app_content, modal_content, Action::CloseComparison)
+        } else if self.batch_rename.is_some() {
+            let modal_content = self.batch_rename_modal_content();
+            modal(app_content, modal_content, Action::CloseBatchRename)
+        } else if self.command_palette_open {
+            let modal_content = self.command_palette_content();
+            modal(app_content, modal_content, Action::CloseCommandPalette)
         } else {
             app_content
         }
     }
 
     fn side_bar(&self) -> iced::Element<'_, Action> {
+        let font_size = self.row_density.font_size();
+        let row_padding = [self.row_density.padding(), 10.0];
+
         let back_button: iced::Element<Action> = button(row![
             text("⬆️ ../")
                 .shaping(text::Shaping::Advanced)
-                .size(FILE_NAME_FONT_SIZE)
+                .size(font_size)
         ])
         .on_press(Action::GoBack())
         .style(file_node_style(false))
+        .padding(row_padding)
         .width(Length::Fill)
         .into();
 
         let mut file_nodes: Vec<iced::Element<Action>> = Vec::new();
 
-        for (index, f) in self.files.iter().enumerate() {
-            if !f.matches_filters {
-                continue;
+        for (index, f) in self.paged_files() {
+            let thumbnail = self.show_thumbnails.then(|| {
+                self.thumbnails.get(&ThumbnailKey {
+                    path: f.absolute_path.clone(),
+                    modified_at: f.modified_at.clone(),
+                })
+            });
+
+            let (stem, extension) = f.split_extension();
+
+            // Show the thumbnail of images, or their icon while it is generated
+            let mut file_name_row = match thumbnail.flatten() {
+                Some(handle) => row![
+                    image(handle.clone())
+                        .width(THUMBNAIL_DISPLAY_SIZE)
+                        .height(THUMBNAIL_DISPLAY_SIZE),
+                    text(match self.extension_display {
+                        ExtensionDisplay::Inline => f.file_name.as_str(),
+                        ExtensionDisplay::Hidden | ExtensionDisplay::Column => stem,
+                    })
+                    .size(font_size)
+                ],
+                None => row![file_name_text(f, font_size, self.extension_display)],
             }
+            .push(link_target_text(f, font_size))
+            .push(self.child_count_text(f, font_size))
+            .push(space::horizontal().width(Length::Fill))
+            .push(
+                text(f.human_readable_size())
+                    .size(font_size * 0.75)
+                    .width(SIZE_COLUMN_WIDTH)
+                    .align_x(Alignment::End),
+            )
+            .push(
+                text(self.modified_time(f))
+                    .size(font_size * 0.75)
+                    .width(MODIFIED_COLUMN_WIDTH)
+                    .align_x(Alignment::End),
+            )
+            .spacing(5.0)
+            .align_y(Alignment::Center);
 
-            let file_name_row = text(f.display_name())
-                .shaping(text::Shaping::Advanced)
-                .size(FILE_NAME_FONT_SIZE);
+            if self.extension_display == ExtensionDisplay::Column {
+                file_name_row = file_name_row.push(
+                    text(extension.unwrap_or_default())
+                        .size(font_size * 0.75)
+                        .width(EXTENSION_COLUMN_WIDTH),
+                );
+            }
+
+            if let Some(status) = f.git_status {
+                file_name_row = file_name_row.push(
+                    text(status.marker())
+                        .size(font_size)
+                        .font(Font::MONOSPACE)
+                        .color(git_status_color(status)),
+                );
+            }
 
             let is_selected = match &self.opened_file {
                 Some(opened_file) => opened_file.absolute_path == f.absolute_path,
                 None => false,
+            } || self.selection.contains(&f.absolute_path);
+            // The row moved to with the keyboard is outlined
+            let is_focused = self.selected_index == Some(index);
+
+            // The row of the file being renamed is replaced by the field naming it
+            if let Some((path, new_name)) = &self.renaming
+                && *path == f.absolute_path
+            {
+                file_nodes.push(
+                    text_input("New name", new_name)
+                        .id(RENAME_INPUT_ID)
+                        .on_input(Action::SetRenameName)
+                        .on_submit(Action::Rename(index, new_name.clone()))
+                        .size(font_size)
+                        .padding(row_padding)
+                        .width(Length::Fill)
+                        .into(),
+                );
+                continue;
+            }
+
+            // Directories expand in place with their arrow, or open when clicked
+            let expander: iced::Element<Action> = if f.is_dir {
+                button(text(if f.expanded { "▼" } else { "▶" }).size(font_size * 0.75))
+                    .on_press(Action::ToggleExpanded(index))
+                    .style(button::text)
+                    .padding(0.0)
+                    .width(TREE_EXPANDER_WIDTH)
+                    .into()
+            } else {
+                space::horizontal().width(TREE_EXPANDER_WIDTH).into()
             };
 
-            file_nodes.push(add_context_menu_to(
-                index,
+            let file_button = mouse_area(
                 button(file_name_row)
-                    .style(file_node_style(is_selected))
+                    .style(tree_node_style(is_selected, is_focused))
                     .on_press(Action::OpenFile(index))
-                    .width(Length::Fill)
+                    .padding(row_padding)
+                    .width(Length::Fill),
+            )
+            .on_enter(Action::HoverFile(index));
+
+            // Show the first lines of text files when hovering them
+            let file_row: iced::Element<Action> =
+                match self.previews.get(&f.absolute_path).and_then(Option::as_ref) {
+                    Some(preview) => tooltip(
+                        file_button,
+                        container(text(preview).size(12.0).font(Font::MONOSPACE))
+                            .padding(10.0)
+                            .max_width(600.0)
+                            .style(container::bordered_box),
+                        tooltip::Position::Right,
+                    )
                     .into(),
-            ));
+                    None => file_button.into(),
+                };
+
+            let file_row = row![
+                space::horizontal().width(f.depth as f32 * self.tree_indent.width()),
+                expander,
+                file_row
+            ]
+            .align_y(Alignment::Center)
+            .into();
+
+            let extractable = f.source == FileSource::Disk && is_extractable(&f.absolute_path);
+            file_nodes.push(add_context_menu_to(index, extractable, file_row));
         }
 
         let left_border = container(text(""))
@@ -95,22 +347,99 @@ impl FileExplorerApp {
                 column![
                     // Directory name and search bar
                     column![
-                        text(self.opened_dir.display_name())
-                            .size(HEADING_FONT_SIZE)
-                            .font(Font {
-                                weight: Weight::Bold,
-                                ..Font::default()
-                            }),
-                        text_input("Search file names", &self.filters.file_name_search)
-                            .on_input(Action::DebouncedSearch)
-                            .width(Length::Fill),
+                        self.tab_strip(),
+                        row![
+                            text(self.opened_dir.display_name())
+                                .size(HEADING_FONT_SIZE)
+                                .font(Font {
+                                    weight: Weight::Bold,
+                                    ..Font::default()
+                                }),
+                            self.bookmark_toggle(),
+                        ]
+                        .spacing(5.0)
+                        .align_y(Alignment::Center),
+                        self.breadcrumbs(),
+                        self.address_bar(),
+                        row![
+                            button(text("⚙️ Settings").shaping(text::Shaping::Advanced))
+                                .on_press(Action::OpenSettingsModal)
+                                .style(button::secondary),
+                            button(text("📁 New Folder").shaping(text::Shaping::Advanced))
+                                .on_press(Action::OpenNewFolder)
+                                .style(button::secondary),
+                            with_label(
+                                button(text("🔄 Refresh").shaping(text::Shaping::Advanced))
+                                    .on_press(Action::Refresh)
+                                    .style(button::secondary),
+                                "Refresh (F5)",
+                            ),
+                            button(text("📋 Paste").shaping(text::Shaping::Advanced))
+                                .on_press_maybe(self.clipboard.is_some().then_some(Action::Paste))
+                                .style(button::secondary),
+                        ]
+                        .spacing(5.0),
+                        self.new_folder_field(),
+                        row![
+                            text_input("Search file names", &self.filters.file_name_search)
+                                .on_input(Action::DebouncedSearch)
+                                .width(Length::Fill),
+                            toggler(self.filters.search_mode == SearchMode::Fuzzy)
+                                .label("Fuzzy")
+                                .on_toggle(|fuzzy| Action::SetSearchMode(if fuzzy {
+                                    SearchMode::Fuzzy
+                                } else {
+                                    SearchMode::Substring
+                                })),
+                            toggler(self.filters.recursive)
+                                .label("Subfolders")
+                                .on_toggle(Action::SetRecursiveSearch),
+                            toggler(self.filters.show_hidden)
+                                .label("Hidden")
+                                .on_toggle(Action::SetShowHidden),
+                        ]
+                        .spacing(5.0)
+                        .align_y(Alignment::Center),
+                        row![
+                            toggler(self.filters.case_sensitive)
+                                .label("Match case")
+                                .on_toggle(Action::SetCaseSensitive),
+                            toggler(self.filters.whole_word)
+                                .label("Whole word")
+                                .on_toggle(Action::SetWholeWord),
+                            toggler(self.filters.search_mode == SearchMode::Regex)
+                                .label("Regex")
+                                .on_toggle(|regex| Action::SetSearchMode(if regex {
+                                    SearchMode::Regex
+                                } else {
+                                    SearchMode::Substring
+                                })),
+                        ]
+                        .spacing(5.0)
+                        .align_y(Alignment::Center),
+                        self.regex_error(),
+                        text_input("Search file contents (Enter)", &self.filters.content_search)
+                            .on_input(Action::SetContentSearch)
+                            .on_submit(Action::SearchByContent(
+                                self.filters.content_search.clone()
+                            )),
+                        self.kind_filter(),
+                        self.quick_access(),
                     ]
+                    .spacing(5.0)
                     .padding(5.0),
                     // File nodes
                     scrollable(column![
                         back_button,
+                        self.loading_indicator(),
+                        self.dir_notice(),
                         iced::widget::Column::from_vec(file_nodes).width(Length::Fill)
-                    ]),
+                    ])
+                    .id(FILE_TREE_SCROLLABLE_ID)
+                    .on_scroll(|viewport| Action::FileTreeScrolled(viewport.absolute_offset().y))
+                    .height(Length::Fill),
+                    self.search_results_notice(),
+                    self.pagination(),
                 ],
                 column![left_border]
             ]
@@ -119,64 +448,689 @@ impl FileExplorerApp {
         .into()
     }
 
+    /// Renders the number of items in a folder next to its name, once counted
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `file` - The file node of the folder
+    /// * `font_size` - The font size of the file tree
+    fn child_count_text(&self, file: &FileNode, font_size: f32) -> iced::Element<'_, Action> {
+        let count = self
+            .show_child_counts
+            .then(|| self.child_counts.get(&file.absolute_path))
+            .flatten()
+            .copied()
+            .flatten();
+        match count {
+            Some(count) => text(format!("({})", count))
+                .size(font_size * 0.75)
+                .style(text::secondary)
+                .into(),
+            None => space::horizontal().width(0.0).into(),
+        }
+    }
+
+    /// Renders the error of a regex search that is not a valid pattern
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn regex_error(&self) -> iced::Element<'_, Action> {
+        match &self.filters.name_regex {
+            Some(NameRegex {
+                compiled: Err(e), ..
+            }) if self.filters.search_mode == SearchMode::Regex => text(e)
+                .size(12.0)
+                .font(Font::MONOSPACE)
+                .style(text::danger)
+                .into(),
+            _ => space::vertical().height(0.0).into(),
+        }
+    }
+
+    /// Renders a notice when a recursive search stopped at the maximum number of results
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn search_results_notice(&self) -> iced::Element<'_, Action> {
+        if !self.search_results_truncated {
+            return space::vertical().height(0.0).into();
+        }
+
+        container(text(format!(
+            "Showing the first {} of many results, refine your query",
+            self.max_search_results
+        )))
+        .padding(5.0)
+        .into()
+    }
+
+    /// Renders a notice while the opened directory is read in the background
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn loading_indicator(&self) -> iced::Element<'_, Action> {
+        if self.pending_dir_load.is_none() {
+            return space::vertical().height(0.0).into();
+        }
+
+        container(text("Loading…")).padding(5.0).into()
+    }
+
+    /// Explains why the file tree is blank when the opened directory can't be
+    /// read or is empty
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn dir_notice(&self) -> iced::Element<'_, Action> {
+        if self.pending_dir_load.is_some() {
+            return space::vertical().height(0.0).into();
+        }
+
+        let notice = match &self.dir_error {
+            Some((std::io::ErrorKind::PermissionDenied, _)) => {
+                String::from("Permission denied. You are not allowed to read this folder.")
+            }
+            Some((_, e)) => format!("Could not read this folder: {}", e),
+            None if self.files.is_empty() && self.showing_search_results => {
+                String::from("No files found")
+            }
+            None if self.files.is_empty() => String::from("This folder is empty"),
+            None => return space::vertical().height(0.0).into(),
+        };
+
+        container(text(notice).style(text::secondary))
+            .center_x(Length::Fill)
+            .padding(20.0)
+            .into()
+    }
+
+    fn new_folder_field(&self) -> iced::Element<'_, Action> {
+        let Some(name) = &self.new_folder_name else {
+            return space::vertical().height(0.0).into();
+        };
+
+        row![
+            text_input("Folder name", name)
+                .id(NEW_FOLDER_INPUT_ID)
+                .on_input(Action::SetNewFolderName)
+                .on_submit(Action::CreateFolder(name.clone()))
+                .width(Length::Fill),
+            button(text("Create")).on_press(Action::CreateFolder(name.clone())),
+            button(text("Cancel"))
+                .on_press(Action::CancelNewFolder)
+                .style(button::secondary),
+        ]
+        .spacing(5.0)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    fn kind_filter(&self) -> iced::Element<'_, Action> {
+        let options = [
+            ("All", KindFilter::All),
+            ("Folders only", KindFilter::FoldersOnly),
+            ("Files only", KindFilter::FilesOnly),
+        ];
+
+        let remembered = self.dir_views.contains_key(&self.opened_dir.absolute_path);
+
+        iced::widget::Row::with_children(options.map(|(label, kind)| {
+            let style = if self.filters.kind == kind {
+                button::primary
+            } else {
+                button::secondary
+            };
+            button(text(label))
+                .on_press(Action::SetKindFilter(kind))
+                .style(style)
+                .into()
+        }))
+        .push(pick_list(
+            &SortMode::ALL[..],
+            Some(self.sort_mode),
+            Action::SetSortMode,
+        ))
+        .push(space::horizontal().width(Length::Fill))
+        .push(
+            toggler(remembered)
+                .label("Remember for this folder")
+                .on_toggle(Action::RememberDirView),
+        )
+        .push(
+            button(text("Apply to all folders"))
+                .on_press(Action::ApplyDirViewToAll)
+                .style(button::secondary),
+        )
+        .spacing(5.0)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    fn tab_strip(&self) -> iced::Element<'_, Action> {
+        if self.tabs.len() < 2 {
+            return space::vertical().height(0.0).into();
+        }
+
+        let tabs = self.tabs.iter().enumerate().map(|(index, tab)| {
+            let is_active = index == self.active_tab;
+            // The entry of the active tab is out of date
+            let dir = if is_active {
+                &self.opened_dir
+            } else {
+                &tab.opened_dir
+            };
+
+            row![
+                button(text(&dir.file_name))
+                    .on_press(Action::SwitchTab(index))
+                    .style(if is_active {
+                        button::primary
+                    } else {
+                        button::secondary
+                    }),
+                with_label(
+                    button(text("✕").shaping(text::Shaping::Advanced))
+                        .on_press(Action::CloseTab(index))
+                        .style(button::text),
+                    "Close the tab",
+                ),
+            ]
+            .align_y(Alignment::Center)
+            .into()
+        });
+
+        scrollable(iced::widget::Row::with_children(tabs).spacing(5.0))
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Scrollbar::default(),
+            ))
+            .into()
+    }
+
+    fn quick_access(&self) -> iced::Element<'_, Action> {
+        let font_size = self.row_density.font_size();
+        let header = button(
+            text(if self.quick_access_open {
+                "▼ Quick Access"
+            } else {
+                "▶ Quick Access"
+            })
+            .shaping(text::Shaping::Advanced),
+        )
+        .on_press(Action::ToggleQuickAccess)
+        .style(button::text)
+        .padding(0.0);
+
+        if !self.quick_access_open {
+            return header.into();
+        }
+
+        let places = self.places.iter().map(|place| {
+            let is_opened = place.path == self.opened_dir.absolute_path;
+            button(
+                text(&place.name)
+                    .shaping(text::Shaping::Advanced)
+                    .size(font_size * 0.75),
+            )
+            .on_press(Action::NavigateTo(place.path.clone()))
+            .style(file_node_style(is_opened))
+            .padding([1.0, 10.0])
+            .width(Length::Fill)
+            .into()
+        });
+
+        // Bookmarks of directories that were moved or deleted are flagged, so they can be removed
+        let bookmarks: Vec<iced::Element<'_, Action>> = self
+            .bookmarks
+            .iter()
+            .map(|path| {
+                let exists = Path::new(path).is_dir();
+                let name = Path::new(path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.clone());
+                let is_opened = *path == self.opened_dir.absolute_path;
+                row![
+                    with_label(
+                        button(
+                            text(if exists {
+                                format!("★ {}", name)
+                            } else {
+                                format!("⚠ {}", name)
+                            })
+                            .shaping(text::Shaping::Advanced)
+                            .size(font_size * 0.75),
+                        )
+                        .on_press_maybe(exists.then(|| Action::NavigateTo(path.clone())))
+                        .style(file_node_style(is_opened))
+                        .padding([1.0, 10.0])
+                        .width(Length::Fill),
+                        if exists {
+                            path.as_str()
+                        } else {
+                            "This folder no longer exists"
+                        },
+                    ),
+                    with_label(
+                        button(text("✕").size(font_size * 0.75))
+                            .on_press(Action::RemoveBookmark(path.clone()))
+                            .style(button::text)
+                            .padding([1.0, 5.0]),
+                        "Remove the bookmark",
+                    ),
+                ]
+                .align_y(Alignment::Center)
+                .into()
+            })
+            .collect();
+
+        // Files that were moved or deleted since they were opened are left out
+        let recent_files: Vec<iced::Element<'_, Action>> = self
+            .recent_files
+            .existing()
+            .map(|path| {
+                let name = Path::new(path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.clone());
+                with_label(
+                    button(
+                        text(format!("🕘 {}", name))
+                            .shaping(text::Shaping::Advanced)
+                            .size(font_size * 0.75),
+                    )
+                    .on_press(Action::NavigateTo(path.clone()))
+                    .style(file_node_style(false))
+                    .padding([1.0, 10.0])
+                    .width(Length::Fill),
+                    path,
+                )
+            })
+            .collect();
+
+        // Places come first, above the bookmarks and recent files
+        let mut section = column![
+            header,
+            text("Places").size(font_size * 0.75),
+            iced::widget::Column::with_children(places).width(Length::Fill)
+        ]
+        .spacing(2.0);
+
+        if !bookmarks.is_empty() {
+            section = section
+                .push(text("Bookmarks").size(font_size * 0.75))
+                .push(iced::widget::Column::with_children(bookmarks).width(Length::Fill));
+        }
+
+        if !recent_files.is_empty() {
+            section = section
+                .push(text("Recent").size(font_size * 0.75))
+                .push(iced::widget::Column::with_children(recent_files).width(Length::Fill));
+        }
+
+        section.into()
+    }
+
+    /// The star bookmarking the opened directory, filled when it is bookmarked
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn bookmark_toggle(&self) -> iced::Element<'_, Action> {
+        // Directories within archives can't be bookmarked
+        if self.opened_dir.source != FileSource::Disk {
+            return space::horizontal().width(0.0).into();
+        }
+
+        let path = self.opened_dir.absolute_path.clone();
+        let (star, action, label) = if self.bookmarks.contains(&path) {
+            ("★", Action::RemoveBookmark(path), "Remove the bookmark")
+        } else {
+            ("☆", Action::AddBookmark(path), "Bookmark this folder")
+        };
+        with_label(
+            button(text(star).shaping(text::Shaping::Advanced))
+                .on_press(action)
+                .style(button::text)
+                .padding([0.0, 5.0]),
+            label,
+        )
+    }
+
+    /// The button hiding or showing the file tree
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn sidebar_toggle(&self) -> iced::Element<'_, Action> {
+        with_label(
+            button(text(if self.sidebar_visible { "◀" } else { "▶" }))
+                .on_press(Action::ToggleSidebar)
+                .style(button::text)
+                .padding([0.0, 5.0]),
+            if self.sidebar_visible {
+                "Hide the file tree (Ctrl+B)"
+            } else {
+                "Show the file tree (Ctrl+B)"
+            },
+        )
+    }
+
+    fn status_bar(&self) -> iced::Element<'_, Action> {
+        let log_toggle = with_label(
+            button(text(format!("Log ({})", logging::entry_count())))
+                .on_press(Action::ToggleLogPanel)
+                .style(button::text)
+                .padding([0.0, 5.0]),
+            "Show or hide the log of the application",
+        );
+
+        let mut status = row![
+            self.sidebar_toggle(),
+            log_toggle,
+            text(self.status_message.clone().unwrap_or_default()),
+            // Cut off on narrow windows rather than wrapping
+            container(
+                text(self.location_summary())
+                    .style(text::secondary)
+                    .wrapping(Wrapping::None)
+            )
+            .width(Length::Fill)
+            .clip(true),
+            self.file_stats(),
+        ]
+        .spacing(10.0)
+        .padding([2.0, 5.0])
+        .align_y(Alignment::Center);
+
+        if let Some(space) = self.disk_space {
+            status = status.push(text(format!(
+                "{} free of {}",
+                human_size(space.available),
+                human_size(space.total)
+            )));
+        }
+
+        if let Some((copied, total)) = self.copy_progress {
+            status = status.push(
+                container(progress_bar(0.0..=total.max(1) as f32, copied as f32).girth(10.0))
+                    .width(200.0),
+            );
+        }
+
+        if let Some((extracted, total)) = self.extract_progress {
+            status = status.push(
+                container(progress_bar(0.0..=total.max(1) as f32, extracted as f32).girth(10.0))
+                    .width(200.0),
+            );
+        }
+
+        status.into()
+    }
+
+    /// Renders the messages captured by the logger, newest last
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn log_panel(&self) -> iced::Element<'_, Action> {
+        let entries = logging::entries().into_iter().map(|entry| {
+            let color = match entry.level {
+                Level::Error => Some(Color::from_rgb(0.9, 0.2, 0.2)),
+                Level::Warn => Some(Color::from_rgb(0.9, 0.6, 0.1)),
+                Level::Info | Level::Debug | Level::Trace => None,
+            };
+
+            text(format!(
+                "{} {:<5} {}",
+                entry.time, entry.level, entry.message
+            ))
+            .size(12.0)
+            .font(Font::MONOSPACE)
+            .color_maybe(color)
+            .into()
+        });
+
+        let header = row![
+            text("Log").font(Font {
+                weight: Weight::Bold,
+                ..Font::default()
+            }),
+            space::horizontal().width(Length::Fill),
+            button("Clear")
+                .on_press(Action::ClearLog)
+                .style(button::secondary),
+            button("Hide")
+                .on_press(Action::ToggleLogPanel)
+                .style(button::secondary),
+        ]
+        .spacing(5.0)
+        .align_y(Alignment::Center);
+
+        container(
+            column![
+                header,
+                scrollable(iced::widget::Column::with_children(entries).width(Length::Fill))
+                    .anchor_bottom()
+                    .height(Length::Fill),
+            ]
+            .spacing(5.0),
+        )
+        .padding(5.0)
+        .height(LOG_PANEL_HEIGHT)
+        .width(Length::Fill)
+        .style(container::bordered_box)
+        .into()
+    }
+
+    /// Summarizes the opened file (its size and type), or the opened directory
+    /// (how many files and folders it has, how many are shown and its path)
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn location_summary(&self) -> String {
+        if let Some(file) = &self.opened_file {
+            return format!(
+                "{} · {} · {}",
+                file.human_readable_size(),
+                mime_type(&file.absolute_path),
+                file.absolute_path
+            );
+        }
+
+        // Only count the children of the opened directory, not of expanded folders
+        let (folders, files) =
+            self.files
+                .iter()
+                .filter(|f| f.depth == 0)
+                .fold((0, 0), |(folders, files), f| {
+                    if f.is_dir {
+                        (folders + 1, files)
+                    } else {
+                        (folders, files + 1)
+                    }
+                });
+        let shown = self.files.iter().filter(|f| f.matches_filters).count();
+
+        format!(
+            "{} folders, {} files · {} shown · {}",
+            folders, files, shown, self.opened_dir.absolute_path
+        )
+    }
+
+    fn file_stats(&self) -> iced::Element<'_, Action> {
+        let mut stats_row = row![].spacing(15.0).align_y(Alignment::Center);
+
+        if let Some(stats) = &self.opened_file_stats {
+            stats_row = stats_row
+                .push(text(&stats.language))
+                .push(text(format!("{} lines", stats.lines)))
+                .push(text(format!("{} characters", stats.characters)));
+        }
+
+        // Allow decoding the file with another encoding
+        if let Some(encoding) = self.opened_file_encoding {
+            stats_row = stats_row.push(
+                pick_list(supported_encodings(), Some(encoding), Action::SetEncoding)
+                    .text_size(12.0)
+                    .padding([0.0, 5.0]),
+            );
+        }
+
+        stats_row.into()
+    }
+
+    fn pagination(&self) -> iced::Element<'_, Action> {
+        let page_count = self.page_count();
+
+        row![
+            button(text("◀ Prev").shaping(text::Shaping::Advanced))
+                .on_press_maybe((self.page > 0).then_some(Action::PreviousPage))
+                .style(button::secondary),
+            space::horizontal().width(Length::Fill),
+            text(format!("Page {} of {}", self.page + 1, page_count)),
+            space::horizontal().width(Length::Fill),
+            button(text("Next ▶").shaping(text::Shaping::Advanced))
+                .on_press_maybe((self.page + 1 < page_count).then_some(Action::NextPage))
+                .style(button::secondary),
+        ]
+        .padding(5.0)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    fn breadcrumbs(&self) -> iced::Element<'_, Action> {
+        let crumbs = breadcrumbs(&self.opened_dir.absolute_path);
+        let last = crumbs.len().saturating_sub(1);
+
+        let mut trail = row![].spacing(2.0).align_y(Alignment::Center);
+        for (index, (label, path)) in crumbs.into_iter().enumerate() {
+            if index > 0 {
+                trail = trail.push(text("›"));
+            }
+            let style = if index == last {
+                button::primary
+            } else {
+                button::text
+            };
+            trail = trail.push(
+                button(text(label).shaping(text::Shaping::Advanced))
+                    .on_press(Action::NavigateTo(path))
+                    .style(style)
+                    .padding([2.0, 5.0]),
+            );
+        }
+
+        let history_back = with_label(
+            button(text("←"))
+                .on_press_maybe((self.history_index > 0).then_some(Action::HistoryBack))
+                .style(button::text)
+                .padding([2.0, 5.0]),
+            "Back (Alt+Left)",
+        );
+        let history_forward = with_label(
+            button(text("→"))
+                .on_press_maybe(
+                    (self.history_index + 1 < self.history.len()).then_some(Action::HistoryForward),
+                )
+                .style(button::text)
+                .padding([2.0, 5.0]),
+            "Forward (Alt+Right)",
+        );
+
+        row![
+            history_back,
+            history_forward,
+            // Long paths scroll sideways instead of wrapping
+            scrollable(trail)
+                .direction(scrollable::Direction::Horizontal(
+                    scrollable::Scrollbar::new().width(4.0).scroller_width(4.0),
+                ))
+                .width(Length::Fill)
+        ]
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    fn address_bar(&self) -> iced::Element<'_, Action> {
+        let input = text_input("Go to path", &self.address_bar.input)
+            .on_input(Action::AddressBarChanged)
+            .on_submit(Action::NavigateTo(self.address_bar.input.clone()))
+            .width(Length::Fill);
+
+        if let Some(error) = &self.address_bar.error {
+            return column![
+                input,
+                text(error).size(14.0).color(Color::from_rgb(1.0, 0.0, 0.0))
+            ]
+            .into();
+        }
+
+        if self.address_bar.completions.len() < 2 {
+            return input.into();
+        }
+
+        // Show the Tab-completion candidates below the input
+        let candidates = self
+            .address_bar
+            .completions
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let is_current = self.address_bar.completion_index == Some(index);
+                button(text(candidate).size(14.0))
+                    .on_press(Action::AddressBarChanged(candidate.clone()))
+                    .style(file_node_style(is_current))
+                    .padding(2.0)
+                    .width(Length::Fill)
+                    .into()
+            })
+            .collect::<Vec<iced::Element<Action>>>();
+
+        column![input, iced::widget::Column::from_vec(candidates)].into()
+    }
+
     fn file_contents(&self) -> iced::Element<'_, Action> {
         let result = match &self.opened_file {
             Some(opened_file) => match &self.opened_file_contents {
                 Ok(contents) => {
-                    let ps = &self.highlighting.syntax_set;
-                    let ts = &self.highlighting.theme_set;
+                    let body: iced::Element<Action> = match self.opened_file_behavior {
+                        OpenBehavior::InternalImage => {
+                            let handle = self.opened_image.as_ref().map_or_else(
+                                || image::Handle::from_path(&opened_file.absolute_path),
+                                |opened| opened.handle.clone(),
+                            );
+                            container(image(handle).content_fit(ContentFit::Contain))
+                                .center(Length::Fill)
+                                .into()
+                        }
+                        _ if self.opened_file_binary => self.binary_notice(opened_file),
+                        _ => {
+                            let direction = if self.opened_file_view.wrap {
+                                scrollable::Direction::default()
+                            } else {
+                                scrollable::Direction::Both {
+                                    vertical: scrollable::Scrollbar::default(),
+                                    horizontal: scrollable::Scrollbar::default(),
+                                }
+                            };
 
-                    let syntax = ps
-                        .find_syntax_by_extension(
-                            &self.opened_file_type.clone().unwrap_or(String::from("txt")),
-                        )
-                        .or(ps.find_syntax_by_extension("txt"))
-                        .unwrap();
-                    let theme = match &self.system_color_mode {
-                        dark_light::Mode::Dark => &ts.themes["base16-ocean.dark"],
-                        dark_light::Mode::Light => &ts.themes["Solarized (light)"],
-                        dark_light::Mode::Unspecified => &ts.themes["Solarized (light)"],
+                            row![
+                                scrollable(self.highlighted_contents(contents))
+                                    .id(FILE_CONTENTS_SCROLLABLE_ID)
+                                    .direction(direction)
+                                    .width(Length::Fill)
+                                    .height(Length::Fill),
+                                self.find_match_strip(contents.lines().count()),
+                            ]
+                            .into()
+                        }
                     };
-                    let mut h = HighlightLines::new(syntax, theme);
-
-                    let lines = contents.lines().collect::<Vec<&str>>();
-                    let line_number_digits = lines.len().to_string().len();
-
-                    let highlighted = iced::widget::Column::with_children(
-                        lines
-                            .iter()
-                            .enumerate()
-                            .map(|(index, line)| {
-                                let spans = h
-                                    .highlight_line(line, &ps)
-                                    .unwrap()
-                                    .iter()
-                                    .map(|(style, text)| {
-                                        span(*text)
-                                            .color(Color::from_rgb8(
-                                                style.foreground.r,
-                                                style.foreground.g,
-                                                style.foreground.b,
-                                            ))
-                                            .font(Font::MONOSPACE)
-                                    })
-                                    .collect::<Vec<Span<String, Font>>>();
-
-                                let rich = Rich::with_spans(spans);
-                                row![
-                                    text(format!(
-                                        "{:width$}",
-                                        index + 1,
-                                        width = line_number_digits
-                                    ))
-                                    .font(Font::MONOSPACE),
-                                    space::vertical().width(Length::Fixed(15.0)),
-                                    rich
-                                ]
-                            })
-                            .map(iced::Element::from)
-                            .collect::<Vec<_>>(),
-                    );
 
                     let top_border = container(text(""))
                         .height(2.0) // The "border" width
@@ -190,6 +1144,8 @@ impl FileExplorerApp {
 
                     column![
                         row![
+                            // Stays reachable in the header while the file tree is hidden
+                            self.sidebar_toggle(),
                             // Opened file name
                             container(text(&opened_file.file_name).size(HEADING_FONT_SIZE).font(
                                 Font {
@@ -198,21 +1154,46 @@ impl FileExplorerApp {
                                 }
                             ))
                             .padding(padding::left(5.0)),
+                            // The size of an opened image
+                            text(
+                                self.opened_image
+                                    .as_ref()
+                                    .map(|opened| format!(
+                                        "{} × {} px",
+                                        opened.width, opened.height
+                                    ))
+                                    .unwrap_or_default()
+                            ),
                             // Empty spave to push the close button to the right
                             space::horizontal().width(Length::Fill),
+                            // View preferences
+                            self.view_controls(),
                             // File Actions
                             container(
-                                button("Close")
-                                    .on_press(Action::CloseFile)
-                                    .style(button::secondary)
+                                row![
+                                    toggler(self.show_file_details)
+                                        .label("Details")
+                                        .on_toggle(|_| Action::ToggleFileDetails),
+                                    button("Open Externally")
+                                        .on_press(Action::OpenViewedFileExternally)
+                                        .style(button::secondary),
+                                    button("Save As...")
+                                        .on_press(Action::SaveAs)
+                                        .style(button::secondary),
+                                    button("Close")
+                                        .on_press(Action::CloseFile)
+                                        .style(button::secondary)
+                                ]
+                                .spacing(5.0)
                             )
                             .padding(padding::right(5.0))
                         ]
                         .align_y(Alignment::Center),
                         top_border,
-                        scrollable(highlighted)
-                            .width(Length::Fill)
-                            .height(Length::Fill)
+                        self.file_details(),
+                        self.truncation_notice(),
+                        self.hex_window_controls(),
+                        body
                     ]
                     .spacing(10.0)
                 }
@@ -238,6 +1219,397 @@ impl FileExplorerApp {
         column!(result).into()
     }
 
+    /// Renders the metadata of the opened file, when the details are shown.
+    /// Fields the platform doesn't record are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn file_details(&self) -> iced::Element<'_, Action> {
+        let Some(details) = self
+            .opened_file_details
+            .as_ref()
+            .filter(|_| self.show_file_details)
+        else {
+            return space::vertical().height(0.0).into();
+        };
+
+        let mut fields = column![
+            labeled("Path", &details.path),
+            labeled(
+                "Size",
+                format!(
+                    "{} ({} bytes)",
+                    human_size(details.size_bytes),
+                    details.size_bytes
+                )
+            ),
+            labeled("Type", details.mime_type),
+        ]
+        .spacing(5.0);
+        if let Some(modified_at) = &details.modified_at {
+            fields = fields.push(labeled("Modified At", modified_at));
+        }
+        if let Some(created_at) = &details.created_at {
+            fields = fields.push(labeled("Created At", created_at));
+        }
+        if let Some(permissions) = &details.permissions {
+            fields = fields.push(labeled("Permissions", permissions));
+        }
+
+        container(fields).padding([0.0, 5.0]).into()
+    }
+
+    /// Renders a description of a binary file opened in the text viewer, with a
+    /// button to show it in the hex viewer instead
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `file` - The opened file
+    fn binary_notice<'a>(&'a self, file: &'a FileNode) -> iced::Element<'a, Action> {
+        container(
+            column![
+                text(format!("Binary file — {}", human_size(file.size_bytes))).size(24.0),
+                text(mime_type(&file.absolute_path)),
+                button("View as Hex")
+                    .on_press(Action::SetRenderMode(OpenBehavior::InternalHex))
+                    .style(button::secondary),
+            ]
+            .spacing(10.0)
+            .align_x(Alignment::Center),
+        )
+        .center(Length::Fill)
+        .into()
+    }
+
+    /// Renders a notice when only the start of the opened text file is shown,
+    /// with a button to read more of it
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn truncation_notice(&self) -> iced::Element<'_, Action> {
+        let Some(file) = self
+            .opened_file
+            .as_ref()
+            .filter(|_| self.opened_file_truncated)
+        else {
+            return space::vertical().height(0.0).into();
+        };
+
+        row![
+            text(format!(
+                "Showing the first {} of {}, as the file is too large to show at once",
+                human_size(self.opened_file_read_limit),
+                human_size(file.size_bytes)
+            )),
+            space::horizontal().width(Length::Fill),
+            button("Load more")
+                .on_press(Action::LoadMoreText)
+                .style(button::secondary),
+        ]
+        .align_y(Alignment::Center)
+        .padding(padding::horizontal(5.0))
+        .into()
+    }
+
+    /// Renders the range of bytes shown in the hex viewer, with buttons to move
+    /// to the previous and next windows of a file too large to show at once
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn hex_window_controls(&self) -> iced::Element<'_, Action> {
+        let Some(file) = self.opened_file.as_ref().filter(|f| {
+            self.opened_file_behavior == OpenBehavior::InternalHex
+                && f.size_bytes > self.hex_view_window
+        }) else {
+            return space::vertical().height(0.0).into();
+        };
+
+        let offset = self.hex_view_offset;
+        let end = (offset + self.hex_view_window).min(file.size_bytes);
+        let previous = offset.saturating_sub(self.hex_view_window);
+
+        row![
+            text(format!(
+                "Bytes {:08x}–{:08x} of {}",
+                offset,
+                end,
+                human_size(file.size_bytes)
+            )),
+            space::horizontal().width(Length::Fill),
+            button("◀ Previous")
+                .on_press_maybe((offset > 0).then_some(Action::SetHexViewOffset(previous)))
+                .style(button::secondary),
+            button("Next ▶")
+                .on_press_maybe((end < file.size_bytes).then_some(Action::SetHexViewOffset(end)))
+                .style(button::secondary),
+        ]
+        .spacing(5.0)
+        .align_y(Alignment::Center)
+        .padding(padding::horizontal(5.0))
+        .into()
+    }
+
+    /// Renders a thin strip next to the contents of the opened file, marking the
+    /// position of each line matching the find query. Clicking a marker scrolls to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `line_count` - The number of lines of the opened file
+    fn find_match_strip(&self, line_count: usize) -> iced::Element<'_, Action> {
+        let mut strip = iced::widget::Column::new()
+            .width(FIND_STRIP_WIDTH)
+            .height(Length::Fill);
+        let mut previous_bucket = 0;
+
+        for &line in &self.find_matches {
+            // Markers are placed in buckets, so huge files still map onto FillPortion
+            let bucket = (line * FIND_STRIP_BUCKETS / line_count.max(1)) as u16;
+            if bucket < previous_bucket {
+                continue;
+            }
+            if bucket > previous_bucket {
+                strip = strip
+                    .push(space::vertical().height(Length::FillPortion(bucket - previous_bucket)));
+            }
+            strip = strip.push(
+                button(space::horizontal())
+                    .on_press(Action::JumpToLine(line))
+                    .width(Length::Fill)
+                    .height(FIND_MARKER_HEIGHT)
+                    .padding(0.0)
+                    .style(button::primary),
+            );
+            previous_bucket = bucket + 1;
+        }
+
+        let last_bucket = FIND_STRIP_BUCKETS as u16;
+        if previous_bucket < last_bucket {
+            strip = strip
+                .push(space::vertical().height(Length::FillPortion(last_bucket - previous_bucket)));
+        }
+
+        strip.into()
+    }
+
+    /// Renders the controls changing how the opened file is presented
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn view_controls(&self) -> iced::Element<'_, Action> {
+        let view = self.opened_file_view;
+        let mut controls = row![].spacing(10.0).align_y(Alignment::Center);
+
+        // Images have a viewer of their own
+        if self.opened_file_behavior != OpenBehavior::InternalImage {
+            controls = controls.push(
+                text_input("Find in file (Ctrl+F)", &self.find_query)
+                    .id(FIND_INPUT_ID)
+                    .on_input(Action::FindQueryChanged)
+                    .on_submit(if self.modifiers.shift() {
+                        Action::FindPrevious
+                    } else {
+                        Action::FindNext
+                    })
+                    .width(200.0),
+            );
+            if !self.find_query.is_empty() {
+                let has_matches = !self.find_matches.is_empty();
+                controls = controls
+                    .push(text(match self.find_current {
+                        Some(current) => {
+                            format!("{} of {}", current + 1, self.find_matches.len())
+                        }
+                        None => format!("{} matches", self.find_matches.len()),
+                    }))
+                    .push(
+                        row![
+                            with_label(
+                                button("▲")
+                                    .on_press_maybe(has_matches.then_some(Action::FindPrevious))
+                                    .style(button::secondary),
+                                "Previous match (Shift+Enter)",
+                            ),
+                            with_label(
+                                button("▼")
+                                    .on_press_maybe(has_matches.then_some(Action::FindNext))
+                                    .style(button::secondary),
+                                "Next match (Enter)",
+                            ),
+                        ]
+                        .spacing(2.0),
+                    );
+            }
+
+            controls = controls
+                .push(pick_list(
+                    &RENDER_MODES[..],
+                    Some(self.opened_file_behavior),
+                    Action::SetRenderMode,
+                ))
+                .push(toggler(view.wrap).label("Wrap").on_toggle(Action::SetWrap))
+                .push(
+                    row![
+                        with_label(
+                            button("A-")
+                                .on_press_maybe((view.font_size > MIN_VIEW_FONT_SIZE).then_some(
+                                    Action::SetViewFontSize(view.font_size - VIEW_FONT_SIZE_STEP)
+                                ))
+                                .style(button::secondary),
+                            "Decrease the font size",
+                        ),
+                        with_label(
+                            button("A+")
+                                .on_press_maybe((view.font_size < MAX_VIEW_FONT_SIZE).then_some(
+                                    Action::SetViewFontSize(view.font_size + VIEW_FONT_SIZE_STEP)
+                                ))
+                                .style(button::secondary),
+                            "Increase the font size",
+                        ),
+                    ]
+                    .spacing(2.0),
+                );
+        }
+
+        if let Some(extension) = self.opened_file_extension() {
+            controls = controls.push(
+                toggler(self.associations.remembers_view(&extension))
+                    .label(format!("Remember for all .{} files", extension))
+                    .on_toggle(Action::RememberViewForType),
+            );
+        }
+
+        controls.into()
+    }
+
+    fn highlighted_contents<'a>(&'a self, contents: &'a str) -> iced::Element<'a, Action> {
+        // The highlighting is computed when the file is opened, not on every frame
+        let highlighted_lines = self
+            .highlighted
+            .as_ref()
+            .map_or(&[][..], |highlighted| &highlighted.lines[..]);
+
+        let lines = contents.lines().collect::<Vec<&str>>();
+        let line_number_digits = lines.len().to_string().len();
+        let font_size = self.opened_file_view.font_size;
+        let current_match = self.find_current.map(|current| self.find_matches[current]);
+        let wrapping = if self.opened_file_view.wrap {
+            Wrapping::Word
+        } else {
+            Wrapping::None
+        };
+
+        iced::widget::Column::with_children(
+            lines
+                .iter()
+                .zip(highlighted_lines)
+                .enumerate()
+                .map(|(index, (line, fragments))| {
+                    // The byte offset where the trailing whitespace of the line starts
+                    let trailing_start = line.trim_end().len();
+                    let mut offset = 0;
+
+                    let spans = fragments
+                        .iter()
+                        .map(|(color, text)| {
+                            let fragment = if self.show_whitespace {
+                                visible_whitespace(text, offset, trailing_start)
+                            } else {
+                                Cow::Borrowed(text.as_str())
+                            };
+                            offset += text.len();
+
+                            span(fragment).color(*color).font(Font::MONOSPACE)
+                        })
+                        .collect::<Vec<Span<String, Font>>>();
+
+                    let rich = Rich::with_spans(spans).size(font_size).wrapping(wrapping);
+                    let mut line_row = row![];
+                    // The numbers are right-aligned and sit at the top of wrapped lines
+                    if self.show_line_numbers {
+                        line_row = line_row
+                            .push(
+                                text(format!("{:width$}", index + 1, width = line_number_digits))
+                                    .size(font_size)
+                                    .font(Font::MONOSPACE)
+                                    .style(text::secondary),
+                            )
+                            .push(space::vertical().width(Length::Fixed(15.0)));
+                    }
+                    let line_row = line_row.push(rich);
+
+                    // Lines matching the find bar are highlighted, the current one more
+                    let is_current = current_match == Some(index);
+                    if !is_current && self.find_matches.binary_search(&index).is_err() {
+                        return iced::Element::from(line_row);
+                    }
+                    container(line_row)
+                        .width(Length::Fill)
+                        .style(move |theme: &Theme| {
+                            let palette = theme.extended_palette();
+                            let color = if is_current {
+                                palette.primary.weak.color
+                            } else {
+                                palette.background.strong.color
+                            };
+                            container::Style {
+                                background: Some(color.into()),
+                                ..Default::default()
+                            }
+                        })
+                        .into()
+                })
+                .collect::<Vec<_>>(),
+        )
+        .into()
+    }
+
+    /// Returns the syntax highlighting themes users can pick from
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    fn syntax_themes(&self) -> Vec<SyntaxTheme> {
+        std::iter::once(SyntaxTheme::Automatic)
+            .chain(
+                self.highlighting
+                    .theme_set
+                    .themes
+                    .keys()
+                    .cloned()
+                    .map(SyntaxTheme::Named),
+            )
+            .collect()
+    }
+
+    fn modified_time(&self, file: &FileNode) -> String {
+        match file.modified {
+            Some(modified) if self.relative_times => relative_time(modified),
+            _ => file.modified_at.clone(),
+        }
+    }
+
+    fn file_info_size(&self, file: &FileNode) -> String {
+        if !file.is_dir {
+            return file.file_size.clone();
+        }
+
+        match self.file_info_dir_size {
+            None => String::from("Calculating..."),
+            Some((size, false)) => human_size(size),
+            Some((size, true)) => format!(
+                "{} (incomplete, the maximum depth of {} was reached)",
+                human_size(size),
+                self.max_walk_depth
+            ),
+        }
+    }
+
     pub fn file_info_modal_content(&self) -> iced::Element<'_, Action> {
         match &self.file_info_modal_node {
             Some(file) => {
@@ -254,10 +1626,15 @@ impl FileExplorerApp {
                             "File"
                         }),
                         labeled("Path", &file.absolute_path),
-                        labeled("Size", &file.file_size),
+                        match &file.link_target {
+                            Some(target) => labeled("Link Target", target),
+                            None => space::vertical().height(0.0).into(),
+                        },
+                        labeled("Size", self.file_info_size(file)),
                         labeled("Created At", &file.created_at),
-                        labeled("Modified At", &file.modified_at),
+                        labeled("Modified At", self.modified_time(file)),
                         labeled("Accessed At", &file.accessed_at),
+                        self.file_hash_row(file),
                         rule::horizontal(2.0),
                         row![
                             // Fill space to push the button
@@ -282,6 +1659,557 @@ impl FileExplorerApp {
             None => column![text("No file info available")].into(),
         }
     }
+
+    /// Renders the hash of a file in the file info modal, with the controls to compute it
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The application instance
+    /// * `file` - The file shown in the file info modal
+    fn file_hash_row<'a>(&'a self, file: &'a FileNode) -> iced::Element<'a, Action> {
+        // Directories have no contents to hash
+        if file.is_dir || file.source != FileSource::Disk {
+            return space::vertical().height(0.0).into();
+        }
+
+        let mut hash_row = row![
+            pick_list(
+                &HashAlgorithm::ALL[..],
+                Some(self.hash_algorithm),
+                Action::SetHashAlgorithm
+            ),
+            button("Compute Hash")
+                .on_press(Action::ComputeHash(file.absolute_path.clone()))
+                .style(button::secondary),
+        ]
+        .spacing(10.0)
+        .align_y(Alignment::Center);
+
+        let file_hash = self
+            .file_hash
+            .as_ref()
+            .filter(|hash| hash.path == file.absolute_path);
+
+        hash_row = match file_hash.map(|hash| (hash, &hash.digest)) {
+            None => hash_row,
+            Some((hash, None)) => {
+                let (hashed, total) = hash.progress;
+                hash_row.push(
+                    container(progress_bar(0.0..=total.max(1) as f32, hashed as f32).girth(10.0))
+                        .width(200.0),
+                )
+            }
+            Some((hash, Some(Ok(digest)))) => hash_row
+                .push(
+                    text(format!("{}: {}", hash.algorithm, digest))
+                        .font(Font::MONOSPACE)
+                        .size(12.0),
+                )
+                .push(
+                    button("Copy")
+                        .on_press(Action::CopyToClipboard(digest.clone()))
+                        .style(button::secondary),
+                ),
+            Some((_, Some(Err(e)))) => hash_row.push(
+                text(format!("Could not hash the file: {}", e))
+                    .color(Color::from_rgb(1.0, 0.0, 0.0)),
+            ),
+        };
+
+        hash_row.into()
+    }
+
+    fn command_palette_content(&self) -> iced::Element<'_, Action> {
+        let commands = filter_commands(self, &self.command_palette_query)
+            .into_iter()
+            .map(|command| {
+                button(text(command.label))
+                    .on_press(Action::RunCommand(Box::new(command.action)))
+                    .style(file_node_style(false))
+                    .width(Length::Fill)
+                    .into()
+            })
+            .collect::<Vec<iced::Element<Action>>>();
+
+        container(
+            column![
+                text_input("Type a command", &self.command_palette_query)
+                    .id(COMMAND_PALETTE_INPUT_ID)
+                    .on_input(Action::CommandPaletteQueryChanged)
+                    .on_submit(Action::RunFirstCommand),
+                scrollable(iced::widget::Column::from_vec(commands)).height(300.0),
+            ]
+            .spacing(10.0)
+            .padding(10.0)
+            .width(500.0),
+        )
+        .style(|style: &Theme| container::Style {
+            background: Some(style.extended_palette().background.base.color.into()),
+            border: border::rounded(5.0),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    fn settings_modal_content(&self) -> iced::Element<'_, Action> {
+        container(
+            column![
+                text("Settings").size(HEADING_FONT_SIZE).font(Font {
+                    weight: Weight::Bold,
+                    ..Font::default()
+                }),
+                setting(
+                    "Color mode",
+                    pick_list(
+                        &ColorMode::ALL[..],
+                        Some(self.color_mode),
+                        Action::SetColorMode
+                    )
+                ),
+                setting(
+                    "Wrap long lines",
+                    pick_list(
+                        &WrapMode::ALL[..],
+                        Some(self.wrap_mode),
+                        Action::SetWrapMode
+                    )
+                ),
+                setting(
+                    "Row density",
+                    pick_list(
+                        &RowDensity::ALL[..],
+                        Some(self.row_density),
+                        Action::SetRowDensity
+                    )
+                ),
+                setting(
+                    "Tree indentation",
+                    pick_list(
+                        &TreeIndent::ALL[..],
+                        Some(self.tree_indent),
+                        Action::SetTreeIndent
+                    )
+                ),
+                setting(
+                    "Open folders in a new tab",
+                    toggler(self.open_dirs_in_new_tab).on_toggle(Action::SetOpenDirsInNewTab)
+                ),
+                setting(
+                    "Show file extensions",
+                    pick_list(
+                        &ExtensionDisplay::ALL[..],
+                        Some(self.extension_display),
+                        Action::SetExtensionDisplay
+                    )
+                ),
+                setting(
+                    "Show whitespace",
+                    toggler(self.show_whitespace).on_toggle(Action::SetShowWhitespace)
+                ),
+                setting(
+                    "Show line numbers",
+                    toggler(self.show_line_numbers).on_toggle(Action::SetShowLineNumbers)
+                ),
+                setting(
+                    "Syntax highlighting theme",
+                    pick_list(
+                        self.syntax_themes(),
+                        Some(self.syntax_theme.clone()),
+                        Action::SetSyntaxTheme
+                    )
+                ),
+                setting(
+                    "Open symlinked folders at their target",
+                    toggler(self.resolve_symlinks).on_toggle(Action::SetResolveSymlinks)
+                ),
+                setting(
+                    "Follow symlinks when searching",
+                    toggler(self.follow_symlinks).on_toggle(Action::SetFollowSymlinks)
+                ),
+                setting(
+                    "Show modified times as relative",
+                    toggler(self.relative_times).on_toggle(Action::SetRelativeTimes)
+                ),
+                setting(
+                    "Show image thumbnails",
+                    toggler(self.show_thumbnails).on_toggle(Action::SetShowThumbnails)
+                ),
+                setting(
+                    "Show the number of items in folders",
+                    toggler(self.show_child_counts).on_toggle(Action::SetShowChildCounts)
+                ),
+                setting(
+                    "Confirm opening folders with more items than",
+                    pick_list(
+                        &LARGE_DIR_THRESHOLD_OPTIONS[..],
+                        Some(self.large_dir_threshold),
+                        Action::SetLargeDirThreshold
+                    )
+                ),
+                setting(
+                    "Maximum search results",
+                    pick_list(
+                        &MAX_SEARCH_RESULTS_OPTIONS[..],
+                        Some(self.max_search_results),
+                        Action::SetMaxSearchResults
+                    )
+                ),
+                setting(
+                    "Bytes shown at once in the hex viewer",
+                    pick_list(
+                        &HEX_VIEW_WINDOW_OPTIONS[..],
+                        Some(self.hex_view_window),
+                        Action::SetHexViewWindow
+                    )
+                ),
+                setting(
+                    "Open files externally with",
+                    text_input("Default application", &self.external_editor)
+                        .on_input(Action::SetExternalEditor)
+                        .width(200.0)
+                ),
+                setting(
+                    "File associations",
+                    button("Edit...")
+                        .on_press(Action::OpenAssociationsModal)
+                        .style(button::secondary)
+                ),
+                rule::horizontal(2.0),
+                row![
+                    space::horizontal().width(Length::Fill),
+                    button("Close")
+                        .on_press(Action::CloseSettingsModal)
+                        .style(button::primary)
+                ]
+                .align_y(Alignment::Center)
+            ]
+            .spacing(20.0)
+            .padding(20.0)
+            .width(500.0),
+        )
+        .style(|style: &Theme| container::Style {
+            background: Some(style.extended_palette().background.base.color.into()),
+            border: border::rounded(5.0),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    fn large_dir_modal_content<'a>(&'a self, dir: &'a FileNode) -> iced::Element<'a, Action> {
+        container(
+            column![
+                text(dir.display_name()).size(HEADING_FONT_SIZE).font(Font {
+                    weight: Weight::Bold,
+                    ..Font::default()
+                }),
+                text(format!(
+                    "This folder has more than {} items and may take a while to open. Continue?",
+                    self.large_dir_threshold
+                )),
+                rule::horizontal(2.0),
+                row![
+                    space::horizontal().width(Length::Fill),
+                    button("Cancel")
+                        .on_press(Action::CancelLargeDir)
+                        .style(button::secondary),
+                    button("Load Anyway")
+                        .on_press(Action::LoadLargeDir)
+                        .style(button::primary)
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center)
+            ]
+            .spacing(20.0)
+            .padding(20.0)
+            .width(500.0),
+        )
+        .style(|style: &Theme| container::Style {
+            background: Some(style.extended_palette().background.base.color.into()),
+            border: border::rounded(5.0),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    fn delete_modal_content<'a>(&'a self, files: &'a [FileNode]) -> iced::Element<'a, Action> {
+        let (heading, warning) = match files {
+            [file] if file.is_dir => (
+                file.display_name(),
+                "This folder and everything in it will be permanently deleted. Continue?",
+            ),
+            [file] => (
+                file.display_name(),
+                "This file will be permanently deleted. Continue?",
+            ),
+            _ => (
+                format!("{} items", files.len()),
+                "These files and folders (and everything in them) will be permanently deleted. Continue?",
+            ),
+        };
+
+        container(
+            column![
+                text(heading)
+                    .size(HEADING_FONT_SIZE)
+                    .shaping(text::Shaping::Advanced)
+                    .font(Font {
+                        weight: Weight::Bold,
+                        ..Font::default()
+                    }),
+                text(warning),
+                rule::horizontal(2.0),
+                row![
+                    space::horizontal().width(Length::Fill),
+                    button("Cancel")
+                        .on_press(Action::CancelDelete)
+                        .style(button::secondary),
+                    button("Delete")
+                        .on_press(Action::ConfirmDelete)
+                        .style(button::danger)
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center)
+            ]
+            .spacing(20.0)
+            .padding(20.0)
+            .width(500.0),
+        )
+        .style(|style: &Theme| container::Style {
+            background: Some(style.extended_palette().background.base.color.into()),
+            border: border::rounded(5.0),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    fn comparison_modal_content<'a>(
+        &'a self,
+        comparison: &'a Comparison,
+    ) -> iced::Element<'a, Action> {
+        let rows = comparison
+            .entries
+            .iter()
+            .map(|entry| {
+                let color = match entry.difference {
+                    Difference::OnlyInLeft => Color::from_rgb(0.9, 0.3, 0.3),
+                    Difference::OnlyInRight => Color::from_rgb(0.3, 0.7, 0.3),
+                    Difference::Differs => Color::from_rgb(0.9, 0.6, 0.1),
+                };
+                let suffix = if entry.is_dir { "/" } else { "" };
+
+                row![
+                    text(entry.difference.to_string())
+                        .color(color)
+                        .width(Length::Fixed(120.0)),
+                    text(format!("{}{}", entry.relative_path.display(), suffix))
+                        .font(Font::MONOSPACE),
+                ]
+                .spacing(10.0)
+                .into()
+            })
+            .collect::<Vec<iced::Element<Action>>>();
+
+        let summary = if comparison.entries.is_empty() {
+            String::from("The directories are the same")
+        } else {
+            format!("{} differences", comparison.entries.len())
+        };
+
+        let mut content = column![
+            text("Compare Directories")
+                .size(HEADING_FONT_SIZE)
+                .font(Font {
+                    weight: Weight::Bold,
+                    ..Font::default()
+                }),
+            labeled("Left", comparison.left.display().to_string()),
+            labeled("Right", comparison.right.display().to_string()),
+            setting(
+                "Compare file contents",
+                toggler(self.compare_contents).on_toggle(Action::SetCompareContents)
+            ),
+            text(summary),
+            scrollable(iced::widget::Column::from_vec(rows).spacing(5.0)).height(300.0),
+        ]
+        .spacing(20.0)
+        .padding(20.0)
+        .width(700.0);
+
+        if comparison.depth_limit_reached {
+            content = content.push(text(format!(
+                "The comparison is incomplete, the maximum depth of {} was reached",
+                self.max_walk_depth
+            )));
+        }
+
+        content = content.push(rule::horizontal(2.0)).push(
+            row![
+                space::horizontal().width(Length::Fill),
+                button("Close")
+                    .on_press(Action::CloseComparison)
+                    .style(button::primary)
+            ]
+            .align_y(Alignment::Center),
+        );
+
+        container(content)
+            .style(|style: &Theme| container::Style {
+                background: Some(style.extended_palette().background.base.color.into()),
+                border: border::rounded(5.0),
+                ..Default::default()
+            })
+            .into()
+    }
+
+    fn batch_rename_modal_content(&self) -> iced::Element<'_, Action> {
+        let Some(batch_rename) = &self.batch_rename else {
+            return space::horizontal().into();
+        };
+
+        let rows = batch_rename
+            .preview
+            .iter()
+            .map(|rename| {
+                let name = |path: &std::path::Path| {
+                    path.file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default()
+                };
+
+                let mut preview_row = row![
+                    text(name(&rename.source)).font(Font::MONOSPACE),
+                    text("→").shaping(text::Shaping::Advanced),
+                    text(name(&rename.destination)).font(Font::MONOSPACE),
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center);
+
+                if let Some(problem) = rename.problem {
+                    preview_row = preview_row
+                        .push(text(format!("({})", problem)).color(Color::from_rgb(0.9, 0.2, 0.2)));
+                }
+
+                preview_row.into()
+            })
+            .collect::<Vec<iced::Element<Action>>>();
+
+        let can_apply = !batch_rename.preview.is_empty()
+            && batch_rename
+                .preview
+                .iter()
+                .all(|rename| rename.problem.is_none());
+
+        container(
+            column![
+                text("Batch Rename").size(HEADING_FONT_SIZE).font(Font {
+                    weight: Weight::Bold,
+                    ..Font::default()
+                }),
+                text_input("Pattern", &batch_rename.pattern)
+                    .on_input(Action::BatchRenamePatternChanged),
+                text(RENAME_PLACEHOLDERS).size(12.0),
+                row![
+                    text_input("Find", &batch_rename.find).on_input(Action::BatchRenameFindChanged),
+                    text_input("Replace with", &batch_rename.replace)
+                        .on_input(Action::BatchRenameReplaceChanged),
+                ]
+                .spacing(10.0),
+                text(format!(
+                    "{} of {} files will be renamed",
+                    batch_rename.preview.len(),
+                    batch_rename.sources.len()
+                )),
+                scrollable(iced::widget::Column::from_vec(rows).spacing(5.0)).height(300.0),
+                rule::horizontal(2.0),
+                row![
+                    space::horizontal().width(Length::Fill),
+                    button("Cancel")
+                        .on_press(Action::CloseBatchRename)
+                        .style(button::secondary),
+                    button("Rename")
+                        .on_press_maybe(can_apply.then_some(Action::ApplyBatchRename))
+                        .style(button::primary)
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center)
+            ]
+            .spacing(20.0)
+            .padding(20.0)
+            .width(600.0),
+        )
+        .style(|style: &Theme| container::Style {
+            background: Some(style.extended_palette().background.base.color.into()),
+            border: border::rounded(5.0),
+            ..Default::default()
+        })
+        .into()
+    }
+
+    fn associations_modal_content(&self) -> iced::Element<'_, Action> {
+        let rows = self
+            .associations
+            .by_extension
+            .iter()
+            .map(|(extension, behavior)| {
+                let ext = extension.clone();
+                row![
+                    text(format!(".{}", extension))
+                        .font(Font::MONOSPACE)
+                        .width(Length::Fixed(100.0)),
+                    pick_list(&OpenBehavior::ALL[..], Some(*behavior), move |b| {
+                        Action::SetAssociation(ext.clone(), b)
+                    }),
+                    space::horizontal().width(Length::Fill),
+                    button("Remove")
+                        .on_press(Action::RemoveAssociation(extension.clone()))
+                        .style(button::danger)
+                ]
+                .spacing(10.0)
+                .align_y(Alignment::Center)
+                .into()
+            })
+            .collect::<Vec<iced::Element<Action>>>();
+
+        let new_extension = self.new_association_extension.clone();
+
+        container(
+            column![
+                text("File Associations")
+                    .size(HEADING_FONT_SIZE)
+                    .font(Font {
+                        weight: Weight::Bold,
+                        ..Font::default()
+                    }),
+                scrollable(iced::widget::Column::from_vec(rows).spacing(5.0)).height(300.0),
+                rule::horizontal(2.0),
+                row![
+                    text_input("Extension (e.g. md)", &self.new_association_extension)
+                        .on_input(Action::NewAssociationExtensionChanged),
+                    pick_list(&OpenBehavior::ALL[..], None::<OpenBehavior>, move |b| {
+                        Action::SetAssociation(new_extension.clone(), b)
+                    })
+                    .placeholder("Add association"),
+                ]
+                .spacing(10.0),
+                row![
+                    space::horizontal().width(Length::Fill),
+                    button("Close")
+                        .on_press(Action::CloseAssociationsModal)
+                        .style(button::primary)
+                ]
+                .align_y(Alignment::Center)
+            ]
+            .spacing(20.0)
+            .padding(20.0)
+            .width(500.0),
+        )
+        .style(|style: &Theme| container::Style {
+            background: Some(style.extended_palette().background.base.color.into()),
+            border: border::rounded(5.0),
+            ..Default::default()
+        })
+        .into()
+    }
 }
 
 fn file_node_style(selected: bool) -> impl Fn(&iced::Theme, button::Status) -> button::Style {
@@ -312,33 +2240,265 @@ fn file_node_style(selected: bool) -> impl Fn(&iced::Theme, button::Status) -> b
     }
 }
 
+/// Styles a row of the file tree like [`file_node_style`], outlining the
+/// focused row so it stands out from the opened one
+fn tree_node_style(
+    selected: bool,
+    focused: bool,
+) -> impl Fn(&iced::Theme, button::Status) -> button::Style {
+    let style = file_node_style(selected);
+    move |theme: &iced::Theme, status: button::Status| {
+        let mut button_style = style(theme, status);
+        if focused {
+            button_style.border = border::rounded(3.0)
+                .width(2.0)
+                .color(theme.extended_palette().primary.strong.color);
+        }
+        button_style
+    }
+}
+
+fn handle_event(event: Event, status: event::Status, _window: window::Id) -> Option<Action> {
+    // Keys typed into text inputs don't move through the file tree or change
+    // files, only the shortcuts moving the focus or closing the command palette
+    // work from within an input
+    let ignored = status == event::Status::Ignored;
+    match event {
+        Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+            Some(Action::ModifiersChanged(modifiers))
+        }
+        Event::Keyboard(keyboard::Event::KeyPressed {
+            key,
+            modifiers,
+            text,
+            ..
+        }) => match key.as_ref() {
+            // Text inputs let Tab through, so whether the address bar has the focus is
+            // checked when completing
+            keyboard::Key::Named(keyboard::key::Named::Tab) => Some(Action::CompleteAddress),
+            // Tab completes the address bar, so F6 moves the focus between the inputs
+            keyboard::Key::Named(keyboard::key::Named::F6) if modifiers.shift() => {
+                Some(Action::FocusPrevious)
+            }
+            keyboard::Key::Named(keyboard::key::Named::F6) => Some(Action::FocusNext),
+            keyboard::Key::Named(keyboard::key::Named::F2) if ignored => {
+                Some(Action::RenameSelected)
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowDown) if ignored => {
+                Some(Action::SelectNext)
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowUp) if ignored => {
+                Some(Action::SelectPrevious)
+            }
+            keyboard::Key::Named(keyboard::key::Named::Enter) if ignored => {
+                Some(Action::OpenSelected)
+            }
+            keyboard::Key::Named(keyboard::key::Named::F5) if ignored => Some(Action::Refresh),
+            keyboard::Key::Named(keyboard::key::Named::ArrowLeft) if ignored && modifiers.alt() => {
+                Some(Action::HistoryBack)
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowRight)
+                if ignored && modifiers.alt() =>
+            {
+                Some(Action::HistoryForward)
+            }
+            keyboard::Key::Named(keyboard::key::Named::Escape) => Some(Action::CloseCommandPalette),
+            keyboard::Key::Character("b") if ignored && modifiers.command() => {
+                Some(Action::ToggleSidebar)
+            }
+            keyboard::Key::Character("t") if ignored && modifiers.command() => Some(Action::NewTab),
+            keyboard::Key::Character("w") if ignored && modifiers.command() => {
+                Some(Action::CloseActiveTab)
+            }
+            // Ctrl+Z within an input undoes typing, never a change to the files
+            keyboard::Key::Character("z") if ignored && modifiers.command() => Some(Action::Undo),
+            keyboard::Key::Character("f") if modifiers.command() => Some(Action::FocusFind),
+            keyboard::Key::Character("p") | keyboard::Key::Character("P")
+                if modifiers.command() && modifiers.shift() =>
+            {
+                Some(Action::ToggleCommandPalette)
+            }
+            // Typing letters while no input has the focus jumps to a file by name
+            _ if ignored && !modifiers.command() && !modifiers.alt() => text
+                .filter(|text| !text.is_empty() && !text.chars().any(char::is_control))
+                .map(|text| Action::TypeAhead(text.to_string())),
+            _ => None,
+        },
+        Event::Window(window::Event::Resized(size)) => Some(Action::WindowResized(size)),
+        _ => None,
+    }
+}
+
+/// Describes what a control does in a tooltip, for controls labeled only with
+/// a symbol or an abbreviation
+///
+/// # Arguments
+///
+/// * `control` - The control to describe
+/// * `label` - The description of the control
+fn with_label<'a>(
+    control: impl Into<iced::Element<'a, Action>>,
+    label: &'a str,
+) -> iced::Element<'a, Action> {
+    tooltip(
+        control,
+        container(text(label).size(12.0))
+            .padding(5.0)
+            .style(container::bordered_box),
+        tooltip::Position::Top,
+    )
+    .into()
+}
+
+/// Renders the display name of a file, highlighting the characters matched by a search
+fn file_name_text(
+    file: &FileNode,
+    font_size: f32,
+    extensions: ExtensionDisplay,
+) -> iced::Element<'_, Action> {
+    if file.match_indices.is_empty() {
+        return text(file.display_name_as(extensions))
+            .shaping(text::Shaping::Advanced)
+            .size(font_size)
+            .into();
+    }
+
+    let (icon, suffix) = match (file.is_dir, file.is_symlink) {
+        (true, false) => ("📂 ", "/"),
+        (true, true) => ("🔗 ", "/"),
+        (false, false) => ("📄 ", ""),
+        (false, true) => ("🔗 ", ""),
+    };
+    let mut spans: Vec<Span<String, Font>> = vec![span(icon)];
+
+    let name = match extensions {
+        ExtensionDisplay::Inline => file.file_name.as_str(),
+        ExtensionDisplay::Hidden | ExtensionDisplay::Column => file.split_extension().0,
+    };
+
+    for (index, c) in name.chars().enumerate() {
+        let character = span(c.to_string());
+        spans.push(if file.match_indices.contains(&index) {
+            character
+                .color(Color::from_rgb8(0xe0, 0x90, 0x20))
+                .font(Font {
+                    weight: Weight::Bold,
+                    ..Font::default()
+                })
+        } else {
+            character
+        });
+    }
+    spans.push(span(suffix));
+
+    Rich::with_spans(spans).size(font_size).into()
+}
+
+/// Renders the target of a symbolic link next to its name
+fn link_target_text(file: &FileNode, font_size: f32) -> iced::Element<'_, Action> {
+    match &file.link_target {
+        Some(target) => text(format!("→ {}", target))
+            .size(font_size * 0.75)
+            .style(text::secondary)
+            .into(),
+        None => space::horizontal().width(0.0).into(),
+    }
+}
+
+fn git_status_color(status: GitStatus) -> Color {
+    match status {
+        GitStatus::Untracked => Color::from_rgb8(0xd0, 0x4a, 0x4a),
+        GitStatus::Added => Color::from_rgb8(0x4a, 0xb0, 0x4a),
+        GitStatus::Modified => Color::from_rgb8(0xd0, 0xa0, 0x30),
+    }
+}
+
 fn add_context_menu_to(
     index: usize,
+    extractable: bool,
     element: iced::Element<'_, Action>,
 ) -> iced::Element<'_, Action> {
     ContextMenu::new(element, move || {
-        // Create a container for the context menu
-        container(column![
+        let mut items = column![
             button(text("Open"))
                 .style(context_menu_button_style())
                 .on_press(Action::OpenFile(index)),
-            //rule::horizontal(2.0),
+            button(text("Open in New Tab"))
+                .style(context_menu_button_style())
+                .on_press(Action::OpenInNewTab(index)),
+            button(text("Open Externally"))
+                .style(context_menu_button_style())
+                .on_press(Action::OpenExternal(index)),
+            rule::horizontal(2.0),
             button(text("Get Info"))
                 .style(context_menu_button_style())
                 .on_press(Action::OpenContextMenu(
                     ContextMenuAction::OpenFileInfoModal(index)
-                ))
-        ])
-        .padding(10.0)
-        // Style the context menu background
-        .style(|theme: &Theme| {
-            return container::Style {
-                background: Some(theme.extended_palette().background.weak.color.into()),
-                border: border::rounded(2.0),
-                ..Default::default()
-            };
-        })
-        .into()
+                )),
+            rule::horizontal(2.0),
+            button(text("Copy"))
+                .style(context_menu_button_style())
+                .on_press(Action::CopyFile(index)),
+            button(text("Cut"))
+                .style(context_menu_button_style())
+                .on_press(Action::CutFile(index)),
+            button(text("Copy To..."))
+                .style(context_menu_button_style())
+                .on_press(Action::OpenContextMenu(ContextMenuAction::CopyTo(index))),
+            button(text("Copy Path"))
+                .style(context_menu_button_style())
+                .on_press(Action::OpenContextMenu(ContextMenuAction::CopyPath(index))),
+            button(text("Copy Name"))
+                .style(context_menu_button_style())
+                .on_press(Action::OpenContextMenu(ContextMenuAction::CopyName(index))),
+            button(text("Copy Name Without Extension"))
+                .style(context_menu_button_style())
+                .on_press(Action::OpenContextMenu(
+                    ContextMenuAction::CopyNameWithoutExtension(index)
+                )),
+            button(text("Copy As Data URI"))
+                .style(context_menu_button_style())
+                .on_press(Action::OpenContextMenu(ContextMenuAction::CopyAsDataUri(
+                    index
+                ))),
+            rule::horizontal(2.0),
+            button(text("Rename"))
+                .style(context_menu_button_style())
+                .on_press(Action::StartRename(index)),
+            button(text("Delete..."))
+                .style(context_menu_button_style())
+                .on_press(Action::Delete(index))
+        ];
+
+        if extractable {
+            items = items
+                .push(rule::horizontal(2.0))
+                .push(
+                    button(text("Extract Here"))
+                        .style(context_menu_button_style())
+                        .on_press(Action::OpenContextMenu(ContextMenuAction::ExtractHere(
+                            index,
+                        ))),
+                )
+                .push(
+                    button(text("Extract To..."))
+                        .style(context_menu_button_style())
+                        .on_press(Action::OpenContextMenu(ContextMenuAction::ExtractTo(index))),
+                );
+        }
+
+        // Create a container for the context menu
+        container(items)
+            .padding(10.0)
+            // Style the context menu background
+            .style(|theme: &Theme| {
+                return container::Style {
+                    background: Some(theme.extended_palette().background.weak.color.into()),
+                    border: border::rounded(2.0),
+                    ..Default::default()
+                };
+            })
+            .into()
     })
     .into()
 }
@@ -392,7 +2552,7 @@ where
     .into()
 }
 
-fn labeled<'a>(label: &'a str, value: &'a str) -> iced::Element<'a, Action> {
+fn labeled<'a>(label: &'a str, value: impl text::IntoFragment<'a>) -> iced::Element<'a, Action> {
     row![
         text(format!("{}: ", label))
             .font(Font {
@@ -404,4 +2564,38 @@ fn labeled<'a>(label: &'a str, value: &'a str) -> iced::Element<'a, Action> {
     ]
     .spacing(10.0)
     .into()
-}
\ No newline at end of file
+}
+
+/// Replaces tabs with arrows and trailing spaces with dots so whitespace is visible
+///
+/// # Arguments
+///
+/// * `text` - The text of a highlighted span
+/// * `offset` - The byte offset of the span within its line
+/// * `trailing_start` - The byte offset where the trailing whitespace of the line starts
+fn visible_whitespace(text: &str, offset: usize, trailing_start: usize) -> Cow<'_, str> {
+    if !text.contains('\t') && offset + text.len() <= trailing_start {
+        return Cow::Borrowed(text);
+    }
+
+    let mut visible = String::with_capacity(text.len());
+    for (index, c) in text.char_indices() {
+        match c {
+            '\t' => visible.push_str("→   "),
+            ' ' if offset + index >= trailing_start => visible.push('·'),
+            c => visible.push(c),
+        }
+    }
+    Cow::Owned(visible)
+}
+
+/// Renders a row of the settings modal with the label on the left and the control on the right
+fn setting<'a>(label: &'a str, control: impl Into<Element<'a, Action>>) -> Element<'a, Action> {
+    row![
+        text(label),
+        space::horizontal().width(Length::Fill),
+        control.into()
+    ]
+    .align_y(Alignment::Center)
+    .into()
+}